@@ -0,0 +1,44 @@
+use syn::{Attribute, Data, DeriveInput, Field, Fields, Lit, Meta, NestedMeta};
+
+/// Returns the named fields of a struct. Fails for enums, unions, tuple and unit structs.
+pub fn named_fields<'a>(input : &'a DeriveInput, derive_name : &str) -> syn::Result<Vec<&'a Field>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().collect()),
+            _ => Err(syn::Error::new_spanned(&input.ident, format!("{} can only be derived for structs with named fields", derive_name))),
+        },
+        _ => Err(syn::Error::new_spanned(&input.ident, format!("{} can only be derived for structs", derive_name))),
+    }
+}
+
+/// Returns the key under which a field is stored: the value of `#[<attribute_name>(key = "...")]` or the field's name.
+pub fn field_key(field : &Field, attribute_name : &str) -> syn::Result<String> {
+    for attribute in attributes_named(&field.attrs, attribute_name) {
+        for nested in nested_metas(attribute)? {
+            if let NestedMeta::Meta(Meta::NameValue(name_value)) = nested {
+                if name_value.path.is_ident("key") {
+                    return match name_value.lit {
+                        Lit::Str(key) => Ok(key.value()),
+                        other => Err(syn::Error::new_spanned(other, "key must be a string literal")),
+                    };
+                }
+            }
+        }
+    }
+
+    let field_name = field.ident.as_ref().expect("named fields always have an identifier");
+    Ok(field_name.to_string())
+}
+
+/// Returns every attribute whose path is `attribute_name`.
+pub fn attributes_named<'a>(attributes : &'a [Attribute], attribute_name : &'a str) -> impl Iterator<Item = &'a Attribute> {
+    attributes.iter().filter(move |attribute| attribute.path.is_ident(attribute_name))
+}
+
+/// Returns the items of an attribute in the form `#[name(item1, item2 = "...")]`.
+pub fn nested_metas(attribute : &Attribute) -> syn::Result<Vec<NestedMeta>> {
+    match attribute.parse_meta()? {
+        Meta::List(list) => Ok(list.nested.into_iter().collect()),
+        other => Err(syn::Error::new_spanned(other, "expected a list of items, e.g. #[name(key = \"...\")]")),
+    }
+}
@@ -0,0 +1,165 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Fields, Generics};
+
+/// Adds `bound` to every type parameter, so generic structs are only encodable when their parameters are.
+fn add_trait_bounds(generics : &Generics, bound : TokenStream) -> Generics {
+    let mut generics = generics.clone();
+    for type_param in generics.type_params_mut() {
+        type_param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+/// Returns the bindings used to destructure 'fields', e.g. `{ a, b }` or `(field_0, field_1)`, and the binding names.
+fn field_bindings(fields : &Fields) -> (TokenStream, Vec<syn::Ident>) {
+    match fields {
+        Fields::Named(named) => {
+            let names : Vec<syn::Ident> = named.named.iter().map(|field| field.ident.clone().expect("named fields always have an identifier")).collect();
+            (quote! { { #(#names),* } }, names)
+        },
+        Fields::Unnamed(unnamed) => {
+            let names : Vec<syn::Ident> = (0..unnamed.unnamed.len()).map(|index| format_ident!("field_{}", index)).collect();
+            (quote! { ( #(#names),* ) }, names)
+        },
+        Fields::Unit => (quote! {}, Vec::new()),
+    }
+}
+
+/// Returns the expression which builds 'fields' from a decoder, e.g. `{ a : Decode::decode_from(decoder)? }`.
+fn decode_fields(fields : &Fields) -> TokenStream {
+    let decode = quote! { ::iota_sc_utils::codec::Decode::decode_from(decoder)? };
+    match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|field| &field.ident);
+            quote! { { #(#names : #decode),* } }
+        },
+        Fields::Unnamed(unnamed) => {
+            let decodes = unnamed.unnamed.iter().map(|_| &decode);
+            quote! { ( #(#decodes),* ) }
+        },
+        Fields::Unit => quote! {},
+    }
+}
+
+/// Generates `Encode`. Structs are encoded as their fields in order. Enums are encoded as the variant index followed by its fields.
+pub fn expand_encode(input : &DeriveInput) -> syn::Result<TokenStream> {
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (bindings, names) = field_bindings(&data.fields);
+            let name = &input.ident;
+            quote! {
+                let #name #bindings = self;
+                #( ::iota_sc_utils::codec::Encode::encode_to(#names, output); )*
+            }
+        },
+        Data::Enum(data) => {
+            let name = &input.ident;
+            let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+                let variant_name = &variant.ident;
+                let index = index as u64;
+                let (bindings, names) = field_bindings(&variant.fields);
+                quote! {
+                    #name::#variant_name #bindings => {
+                        ::iota_sc_utils::codec::write_varint(#index, output);
+                        #( ::iota_sc_utils::codec::Encode::encode_to(#names, output); )*
+                    }
+                }
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        },
+        Data::Union(_) => return Err(syn::Error::new_spanned(&input.ident, "Encode can not be derived for unions")),
+    };
+
+    let name = &input.ident;
+    let generics = add_trait_bounds(&input.generics, quote! { ::iota_sc_utils::codec::Encode });
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::iota_sc_utils::codec::Encode for #name #type_generics #where_clause {
+            #[allow(unused_variables)]
+            fn encode_to(&self, output : &mut ::std::vec::Vec<u8>) {
+                #body
+            }
+        }
+    })
+}
+
+/// Generates `Decode`. Variants of enums are read from their index, which must exist.
+pub fn expand_decode(input : &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = decode_fields(&data.fields);
+            quote! { Ok(#name #fields) }
+        },
+        Data::Enum(data) => {
+            let type_name = name.to_string();
+            let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+                let variant_name = &variant.ident;
+                let index = index as u32;
+                let fields = decode_fields(&variant.fields);
+                quote! { #index => Ok(#name::#variant_name #fields), }
+            });
+            quote! {
+                let variant = decoder.read_varint()?;
+                if variant > u32::MAX as u64 {
+                    return Err(::iota_sc_utils::codec::DecodeError::InvalidInteger);
+                }
+                match variant as u32 {
+                    #(#arms)*
+                    other => Err(::iota_sc_utils::codec::DecodeError::InvalidVariant { type_name : #type_name, variant : other }),
+                }
+            }
+        },
+        Data::Union(_) => return Err(syn::Error::new_spanned(&input.ident, "Decode can not be derived for unions")),
+    };
+
+    let generics = add_trait_bounds(&input.generics, quote! { ::iota_sc_utils::codec::Decode });
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::iota_sc_utils::codec::Decode for #name #type_generics #where_clause {
+            #[allow(unused_variables)]
+            fn decode_from(decoder : &mut ::iota_sc_utils::codec::Decoder) -> ::std::result::Result<Self, ::iota_sc_utils::codec::DecodeError> {
+                #body
+            }
+        }
+    })
+}
+
+/// Generates `MapValue` for a type which implements `Encode`, `Decode` and `Default`, so it can be stored as bytes in params, state and results.
+/// Missing values are read as the default value, like every other `MapValue`.
+pub fn expand_map_value(input : &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let generics = add_trait_bounds(&input.generics, quote! { ::iota_sc_utils::codec::Encode });
+    let mut generics = add_trait_bounds(&generics, quote! { ::iota_sc_utils::codec::Decode });
+    let (_, type_generics, _) = input.generics.split_for_impl();
+    generics.make_where_clause().predicates.push(parse_quote!(#name #type_generics : ::std::default::Default));
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::iota_sc_utils::values::MapValue for #name #type_generics #where_clause {
+            fn set_in(&self, key : &str, mutable_map : &::iota_sc_utils::wasmlib::ScMutableMap) {
+                ::iota_sc_utils::codec::set_encoded_in(key, self, mutable_map);
+            }
+
+            fn get_from(key : &str, immutable_map : &::iota_sc_utils::wasmlib::ScImmutableMap) -> Self {
+                ::iota_sc_utils::codec::get_encoded_value_from(key, immutable_map)
+            }
+
+            fn try_get_from(key : &str, immutable_map : &::iota_sc_utils::wasmlib::ScImmutableMap) -> ::std::result::Result<Self, ::iota_sc_utils::codec::DecodeError> {
+                ::iota_sc_utils::codec::try_get_encoded_value_from(key, immutable_map)
+            }
+
+            fn exists_in(key : &str, immutable_map : &::iota_sc_utils::wasmlib::ScImmutableMap) -> bool {
+                immutable_map.get_bytes(key).exists()
+            }
+
+            fn describe(&self) -> String {
+                ::iota_sc_utils::codec::to_hex(&::iota_sc_utils::codec::Encode::encode(self))
+            }
+        }
+    })
+}
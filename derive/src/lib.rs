@@ -0,0 +1,59 @@
+//! Derive macros for `iota_sc_utils`. Use them through `iota_sc_utils::derive`.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod attributes;
+mod codec;
+mod params;
+mod results;
+
+/// Implements `iota_sc_utils::params::FromParams`. Every field is read from the param named after the field.
+/// The name can be overridden with `#[params(key = "...")]`. Fields are checked against the rules of `iota_sc_utils::validate`,
+/// e.g. `#[params(range(min = 1, max = 100))]`, `#[params(min_len = 3, max_len = 32)]`, `#[params(one_of(1, 2))]`,
+/// `#[params(not_zero_hash)]`, `#[params(custom = "path::to::check")]` and `#[params(custom_ctx = "path::to::check")]`, whose check also gets the context.
+/// The literals of `one_of` have the type of the field. `#[params(optional)]` uses the default value of missing params, so the field type must implement Default.
+#[proc_macro_derive(FromParams, attributes(params))]
+pub fn derive_from_params(input : TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    params::expand_from_params(&input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Implements `iota_sc_utils::results::IntoResults`. Every field is set as a result named after the field.
+/// The name can be overridden with `#[results(key = "...")]`.
+#[proc_macro_derive(IntoResults, attributes(results))]
+pub fn derive_into_results(input : TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    results::expand_into_results(&input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Implements `iota_sc_utils::results::FromResults`. Every field is read from the result named after the field.
+/// The name can be overridden with `#[results(key = "...")]`.
+#[proc_macro_derive(FromResults, attributes(results))]
+pub fn derive_from_results(input : TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    results::expand_from_results(&input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Implements `iota_sc_utils::codec::Encode` for structs and enums.
+#[proc_macro_derive(Encode)]
+pub fn derive_encode(input : TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    codec::expand_encode(&input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Implements `iota_sc_utils::codec::Decode` for structs and enums.
+#[proc_macro_derive(Decode)]
+pub fn derive_decode(input : TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    codec::expand_decode(&input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+/// Implements `iota_sc_utils::values::MapValue` for types implementing `Encode`, `Decode` and `Default`,
+/// so they can be used as params, state, results and items of state collections.
+#[proc_macro_derive(MapValue)]
+pub fn derive_map_value(input : TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    codec::expand_map_value(&input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Field, Lit, Meta, NestedMeta, Path};
+use crate::attributes;
+
+/// Name of the field attribute which overrides the key of a param and adds validation rules,
+/// e.g. `#[params(key = "my_key", range(min = 1, max = 100))]`.
+const ATTRIBUTE_NAME : &str = "params";
+
+/// Rules and options of a field.
+struct FieldParams {
+    optional : bool,
+    rules : Vec<TokenStream>,
+}
+
+/// Returns the tokens of a rule argument. String literals are parsed as expressions, so negative numbers can be written as "-1".
+fn argument_tokens(lit : &Lit) -> syn::Result<TokenStream> {
+    match lit {
+        Lit::Str(text) => TokenStream::from_str(&text.value()).map_err(|_| syn::Error::new_spanned(text, "invalid expression")),
+        other => Ok(quote! { #other }),
+    }
+}
+
+/// Returns the literal 'name = ...' of a list of items, such as the ones of `range(min = 1, max = 100)`.
+fn named_argument<'a>(items : &'a [NestedMeta], name : &str) -> Option<&'a Lit> {
+    items.iter().find_map(|item| match item {
+        NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident(name) => Some(&name_value.lit),
+        _ => None,
+    })
+}
+
+/// Reads the rules and options of `#[params(...)]` attributes of a field.
+fn field_params(field : &Field) -> syn::Result<FieldParams> {
+    let field_type = &field.ty;
+    let mut field_params = FieldParams { optional : false, rules : Vec::new() };
+    for attribute in attributes::attributes_named(&field.attrs, ATTRIBUTE_NAME) {
+        for nested in attributes::nested_metas(attribute)? {
+            match &nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("optional") => field_params.optional = true,
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("not_zero_hash") => {
+                    field_params.rules.push(quote! { ::iota_sc_utils::validate::not_zero_hash() });
+                },
+                NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("key") => {},
+                NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("min_len") || name_value.path.is_ident("max_len") => {
+                    let rule = name_value.path.get_ident().expect("checked to be an identifier");
+                    let length = argument_tokens(&name_value.lit)?;
+                    field_params.rules.push(quote! { ::iota_sc_utils::validate::#rule(#length) });
+                },
+                NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("custom") => {
+                    let check : Path = match &name_value.lit {
+                        Lit::Str(path) => path.parse()?,
+                        other => return Err(syn::Error::new_spanned(other, "custom must be the path of a function as a string literal")),
+                    };
+                    field_params.rules.push(quote! { ::iota_sc_utils::validate::custom::<#field_type, _>(#check) });
+                },
+                NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("custom_ctx") => {
+                    let check : Path = match &name_value.lit {
+                        Lit::Str(path) => path.parse()?,
+                        other => return Err(syn::Error::new_spanned(other, "custom_ctx must be the path of a function as a string literal")),
+                    };
+                    field_params.rules.push(quote! { ::iota_sc_utils::validate::custom::<#field_type, _>(|value : &#field_type| #check(value, ctx)) });
+                },
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("range") => {
+                    let items : Vec<NestedMeta> = list.nested.iter().cloned().collect();
+                    let (min, max) = match (named_argument(&items, "min"), named_argument(&items, "max")) {
+                        (Some(min), Some(max)) => (argument_tokens(min)?, argument_tokens(max)?),
+                        _ => return Err(syn::Error::new_spanned(list, "expected range(min = ..., max = ...)")),
+                    };
+                    field_params.rules.push(quote! { ::iota_sc_utils::validate::range::<#field_type>(#min, #max) });
+                },
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("one_of") => {
+                    let mut allowed = Vec::new();
+                    for item in list.nested.iter() {
+                        match item {
+                            NestedMeta::Lit(lit) => allowed.push(lit),
+                            other => return Err(syn::Error::new_spanned(other, "one_of expects literals")),
+                        }
+                    }
+                    // String literals are compared as '&str', every other literal gets the type of the field, so '1' is an i64 for an i64 field.
+                    let strings = allowed.iter().filter(|lit| matches!(lit, Lit::Str(_))).count();
+                    let allowed_type = match strings {
+                        0 => quote! { #field_type },
+                        count if count == allowed.len() => quote! { &str },
+                        _ => return Err(syn::Error::new_spanned(list, "one_of expects either only string literals or none")),
+                    };
+                    field_params.rules.push(quote! { ::iota_sc_utils::validate::one_of::<#allowed_type>(&[#(#allowed),*]) });
+                },
+                other => return Err(syn::Error::new_spanned(other, "unknown params item. Expected key, optional, range, min_len, max_len, one_of, not_zero_hash, custom or custom_ctx")),
+            }
+        }
+    }
+    Ok(field_params)
+}
+
+/// Generates `FromParams`: every field is read from the params under its key and checked against its rules.
+pub fn expand_from_params(input : &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = attributes::named_fields(input, "FromParams")?;
+    let mut reads = Vec::new();
+    let mut getters = Vec::new();
+    for field in fields {
+        let field_name = &field.ident;
+        let field_type = &field.ty;
+        let key = attributes::field_key(field, ATTRIBUTE_NAME)?;
+        let field_params = field_params(field)?;
+        let rules = field_params.rules;
+        let read_func = match field_params.optional {
+            true => quote! { optional_param },
+            false => quote! { param },
+        };
+        reads.push(quote! {
+            let #field_name = validator.#read_func::<#field_type>(#key, &params, &[#(&#rules as &dyn ::iota_sc_utils::validate::Rule<#field_type>),*]);
+        });
+        // Missing optional params are the default value. Missing required params are violations, so they are known to exist after finish.
+        getters.push(match field_params.optional {
+            true => quote! { #field_name : #field_name.unwrap_or_default() },
+            false => quote! { #field_name : #field_name.expect("a missing or undecodable parameter is a violation, so finish panics") },
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::iota_sc_utils::params::FromParams for #name #type_generics #where_clause {
+            fn must_read_params<TContext : ::iota_sc_utils::wasmlib::ScBaseContext>(ctx : &TContext) -> Self {
+                let params = ctx.params();
+                let mut validator = ::iota_sc_utils::validate::Validator::new();
+                #(#reads)*
+                validator.finish(ctx);
+                #name { #(#getters),* }
+            }
+        }
+    })
+}
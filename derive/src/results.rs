@@ -0,0 +1,62 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+use crate::attributes;
+
+/// Name of the field attribute which overrides the key of a result, e.g. `#[results(key = "my_key")]`.
+const ATTRIBUTE_NAME : &str = "results";
+
+/// Generates `IntoResults`: every field is set as a result under its key.
+pub fn expand_into_results(input : &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = attributes::named_fields(input, "IntoResults")?;
+    let mut setters = Vec::new();
+    for field in fields {
+        let field_name = &field.ident;
+        let key = attributes::field_key(field, ATTRIBUTE_NAME)?;
+        setters.push(quote! {
+            ::iota_sc_utils::values::MapValue::set_in(&self.#field_name, #key, results);
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::iota_sc_utils::results::IntoResults for #name #type_generics #where_clause {
+            fn write_results(&self, results : &::iota_sc_utils::wasmlib::ScMutableMap) {
+                #(#setters)*
+            }
+        }
+    })
+}
+
+/// Generates `FromResults`: every field is read from the results under its key.
+pub fn expand_from_results(input : &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = attributes::named_fields(input, "FromResults")?;
+    let mut must_getters = Vec::new();
+    let mut getters = Vec::new();
+    for field in fields {
+        let field_name = &field.ident;
+        let field_type = &field.ty;
+        let key = attributes::field_key(field, ATTRIBUTE_NAME)?;
+        must_getters.push(quote! {
+            #field_name : ::iota_sc_utils::results::must_get::<#field_type>(#key, results.clone())
+        });
+        getters.push(quote! {
+            #field_name : ::iota_sc_utils::results::get::<#field_type>(#key, results.clone())
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::iota_sc_utils::results::FromResults for #name #type_generics #where_clause {
+            fn must_read_results(results : &::iota_sc_utils::wasmlib::ScImmutableMap) -> Self {
+                #name { #(#must_getters),* }
+            }
+
+            fn read_results(results : &::iota_sc_utils::wasmlib::ScImmutableMap) -> Self {
+                #name { #(#getters),* }
+            }
+        }
+    })
+}
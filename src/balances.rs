@@ -0,0 +1,55 @@
+use wasmlib::*;
+use crate::incoming;
+use crate::math::SafeMath;
+use crate::tokens::{ColorKey, Tokens};
+
+/// Returns the amount of tokens of 'color' held by the contract, including the ones attached to the current call.
+pub fn balance<TContext : ScBaseContext>(color : &ScColor, ctx : &TContext) -> i64 {
+    let balance : i64 = ctx.balances().balance(color);
+    balance
+}
+
+/// Returns a list of colors of the tokens held by the contract.
+pub fn colors<TContext : ScBaseContext>(ctx : &TContext) -> ScImmutableColorArray {
+    let own_colors : ScImmutableColorArray = ctx.balances().colors();
+    own_colors
+}
+
+/// Returns every amount of tokens held by the contract, by color.
+pub fn all<TContext : ScBaseContext>(ctx : &TContext) -> Tokens {
+    let balances = ctx.balances();
+    let own_colors = balances.colors();
+    let mut tokens = Tokens::new();
+    for index in 0..own_colors.length() {
+        let color = own_colors.get_color(index).value();
+        let amount = balances.balance(&color);
+        tokens.insert(ColorKey(color), amount);
+    }
+    tokens
+}
+
+/// Panics if the contract holds less than 'minimum_balance' tokens of 'color', naming the color and the shortfall.
+pub fn require_balance<TContext : ScBaseContext>(minimum_balance : i64, color : &ScColor, ctx : &TContext) {
+    let own_balance = balance(color, ctx);
+    if own_balance < minimum_balance {
+        let shortfall = minimum_balance.safe_sub(&own_balance, ctx);
+        let message = format!("Insufficient balance of color {}. Required: {}, available: {}, shortfall: {}.", color.to_string(), minimum_balance, own_balance, shortfall);
+        ctx.panic(&message);
+        panic!("{}", message)
+    }
+}
+
+/// Returns the amount of tokens of 'color' the contract held before the current call, i.e. excluding the attached ones.
+pub fn balance_before_incoming(color : &ScColor, ctx : &ScFuncContext) -> i64 {
+    balance(color, ctx).safe_sub(&incoming::balance(color, ctx), ctx)
+}
+
+/// Returns every amount of tokens the contract held before the current call, by color. Colors which only came attached to it are left out.
+pub fn all_before_incoming(ctx : &ScFuncContext) -> Tokens {
+    let mut tokens = all(ctx);
+    for (color, amount) in tokens.iter_mut() {
+        *amount = amount.safe_sub(&incoming::balance(color, ctx), ctx);
+    }
+    tokens.retain(|_, amount| *amount > 0);
+    tokens
+}
@@ -0,0 +1,282 @@
+use wasmlib::*;
+use crate::codec::Decode;
+use crate::params::{self, MAX_ARRAY_LENGTH};
+use crate::results::FromResults;
+use crate::values::{ArrayValue, IntoMapValue, MapValue};
+use crate::wire;
+
+/// Starts building a call to the function 'hfunction' of the contract 'hcontract'.
+/// # Sample:
+/// ```ignore
+/// let results : CallResult = calls::call(HNAME_CONTRACT, HNAME_FUNC_TRANSFER)
+///     .param("amount", 1i64)
+///     .param("recipient", &recipient)
+///     .transfer(&ScColor::IOTA, 100)
+///     .call(ctx);
+/// let balance : i64 = results.must_get("balance");
+/// ```
+pub fn call(hcontract : ScHname, hfunction : ScHname) -> CallBuilder {
+    CallBuilder { hcontract, hfunction, params : None, transfers : None, chain_id : None, delay : 0 }
+}
+
+/// Builds the params and transfers of a call or post. Created with `calls::call`.
+pub struct CallBuilder {
+    hcontract : ScHname,
+    hfunction : ScHname,
+    params : Option<ScMutableMap>,
+    transfers : Option<ScTransfers>,
+    chain_id : Option<ScChainID>,
+    delay : i64,
+}
+
+impl CallBuilder {
+    /// Adds the param 'key'. Accepts any type supported by `MapValue`, by value or by reference.
+    pub fn param<T : IntoMapValue>(mut self, key : &str, value : T) -> CallBuilder {
+        let params = self.params.get_or_insert_with(ScMutableMap::new);
+        value.set_into(key, params);
+        self
+    }
+
+    /// Transfers 'amount' tokens of 'color' with the call. Setting the same color again replaces its amount.
+    pub fn transfer(mut self, color : &ScColor, amount : i64) -> CallBuilder {
+        self.transfers.get_or_insert_with(ScTransfers::new).set(color, amount);
+        self
+    }
+
+    /// Posts to the contract on 'chain_id' instead of the current chain. Only used by `post`.
+    pub fn chain(mut self, chain_id : &ScChainID) -> CallBuilder {
+        self.chain_id = Some(ScChainID::from_bytes(chain_id.to_bytes()));
+        self
+    }
+
+    /// Delays the request by 'seconds'. Only used by `post`.
+    pub fn delay(mut self, seconds : i64) -> CallBuilder {
+        self.delay = seconds;
+        self
+    }
+
+    /// Calls the function synchronously from a func and returns its results.
+    pub fn call(self, ctx : &ScFuncContext) -> CallResult {
+        let results = ctx.call(ScHname(self.hcontract.0), ScHname(self.hfunction.0), self.params, self.transfers);
+        CallResult::new(results, self.hcontract, self.hfunction)
+    }
+
+    /// Calls the view synchronously from a view and returns its results. Panics if tokens were added with `transfer`.
+    pub fn call_view(self, ctx : &ScViewContext) -> CallResult {
+        ctx.require(self.transfers.is_none(), "Views can't transfer tokens.");
+        let results = ctx.call(ScHname(self.hcontract.0), ScHname(self.hfunction.0), self.params);
+        CallResult::new(results, self.hcontract, self.hfunction)
+    }
+
+    /// Posts a request to the function, on the current chain unless `chain` was used, after `delay` seconds.
+    pub fn post(self, ctx : &ScFuncContext) {
+        let chain_id = self.chain_id.unwrap_or_else(|| ctx.chain_id());
+        let transfers = self.transfers.unwrap_or_else(ScTransfers::new);
+        ctx.post(&chain_id, self.hcontract, self.hfunction, self.params, transfers, self.delay);
+    }
+}
+
+macro_rules! add_impl_result_getters {
+    ($must_get_func_name:ident, $get_func_name:ident, $exists_func_name:ident, $return_type:ty) => {
+        /// Tries to get a result. Panics if it can't find it.
+        pub fn $must_get_func_name(&self, result_name : &str) -> $return_type {
+            self.must_get(result_name)
+        }
+
+        /// Tries to get a result. Returns default value if it can't find it.
+        pub fn $get_func_name(&self, result_name : &str) -> $return_type {
+            self.get(result_name)
+        }
+
+        /// Checks if a result exists. Returns true if it exists.
+        pub fn $exists_func_name(&self, result_name : &str) -> bool {
+            self.exists::<$return_type>(result_name)
+        }
+    };
+}
+
+macro_rules! add_impl_result_array_getters {
+    ($must_get_func_name:ident, $get_func_name:ident, $item_type:ty) => {
+        /// Tries to get a result array of at most MAX_ARRAY_LENGTH items. Panics if it can't find it, it is too long,
+        /// it has holes, or an item has another type.
+        pub fn $must_get_func_name(&self, result_name : &str) -> Vec<$item_type> {
+            self.must_get_array(result_name, MAX_ARRAY_LENGTH)
+        }
+
+        /// Tries to get a result array of at most MAX_ARRAY_LENGTH items. Returns an empty Vec if it can't find it.
+        /// Panics if it is too long, it has holes, or an item has another type.
+        pub fn $get_func_name(&self, result_name : &str) -> Vec<$item_type> {
+            self.get_array(result_name, MAX_ARRAY_LENGTH)
+        }
+    };
+}
+
+/// Results of a call, which remembers the called contract and function for error messages.
+/// Getters borrow the results, so any amount of them can be read without cloning the map.
+pub struct CallResult {
+    results : ScImmutableMap,
+    hcontract : ScHname,
+    hfunction : ScHname,
+}
+
+impl CallResult {
+    /// Wraps 'results' returned by calling the function 'hfunction' of the contract 'hcontract'.
+    pub fn new(results : ScImmutableMap, hcontract : ScHname, hfunction : ScHname) -> CallResult {
+        CallResult { results, hcontract, hfunction }
+    }
+
+    /// Returns the results as an ScImmutableMap.
+    pub fn results(&self) -> &ScImmutableMap {
+        &self.results
+    }
+
+    /// Returns the hname of the called contract.
+    pub fn hcontract(&self) -> ScHname {
+        ScHname(self.hcontract.0)
+    }
+
+    /// Returns the hname of the called function.
+    pub fn hfunction(&self) -> ScHname {
+        ScHname(self.hfunction.0)
+    }
+
+    /// Returns the nested map 'result_name', e.g. one written with `ResultsWriter::map`.
+    pub fn get_map(&self, result_name : &str) -> CallResult {
+        CallResult::new(self.results.get_map(result_name), self.hcontract(), self.hfunction())
+    }
+
+    /// Returns the nested maps of the array 'result_name', e.g. the ones written with `ResultsWriter::push_map`.
+    pub fn get_maps(&self, result_name : &str) -> Vec<CallResult> {
+        let maps = self.results.get_map_array(result_name);
+        (0..maps.length()).map(|index| CallResult::new(maps.get_map(index), self.hcontract(), self.hfunction())).collect()
+    }
+
+    /// Describes the called function in error messages.
+    fn description(&self, result_name : &str) -> String {
+        format!("result {} of {}.{}", result_name, self.hcontract.to_string(), self.hfunction.to_string())
+    }
+
+    /// Tries to get a result of any type supported by `MapValue`. Panics if it can't find it.
+    pub fn must_get<T : MapValue>(&self, result_name : &str) -> T {
+        if !T::exists_in(result_name, &self.results) {
+            panic!("{} not found", self.description(result_name));
+        }
+        T::get_from(result_name, &self.results)
+    }
+
+    /// Tries to get a result of any type supported by `MapValue`. Returns default value if it can't find it.
+    pub fn get<T : MapValue>(&self, result_name : &str) -> T {
+        T::get_from(result_name, &self.results)
+    }
+
+    /// Checks if a result of any type supported by `MapValue` exists. Returns true if it exists.
+    pub fn exists<T : MapValue>(&self, result_name : &str) -> bool {
+        T::exists_in(result_name, &self.results)
+    }
+
+    // Primitive types
+    add_impl_result_getters!(must_get_string, get_string, exists_string, String);
+    add_impl_result_getters!(must_get_int64, get_int64, exists_int64, i64);
+    add_impl_result_getters!(must_get_bytes, get_bytes, exists_bytes, Vec<u8>);
+
+    /// Tries to get a bool result. Panics if it can't find it or it is not a valid bool.
+    pub fn must_get_bool(&self, result_name : &str) -> bool {
+        let bytes : Vec<u8> = self.must_get(result_name);
+        self.decode_bool(result_name, &bytes)
+    }
+
+    /// Tries to get a bool result. Returns false if it can't find it. Panics if it is not a valid bool.
+    pub fn get_bool(&self, result_name : &str) -> bool {
+        let result = self.results.get_bytes(result_name);
+        result.exists() && self.decode_bool(result_name, &result.value())
+    }
+
+    /// Checks if a bool result exists. Returns true if it exists.
+    pub fn exists_bool(&self, result_name : &str) -> bool {
+        self.exists::<bool>(result_name)
+    }
+
+    fn decode_bool(&self, result_name : &str, bytes : &[u8]) -> bool {
+        match wire::decode_bool(bytes) {
+            Ok(value) => value,
+            Err(error) => panic!("{} is not a valid bool. Reason: {}", self.description(result_name), error),
+        }
+    }
+
+    // ISCP types
+    add_impl_result_getters!(must_get_agent_id, get_agent_id, exists_agent_id, ScAgentID);
+    add_impl_result_getters!(must_get_address, get_address, exists_address, ScAddress);
+    add_impl_result_getters!(must_get_request_id, get_request_id, exists_request_id, ScRequestID);
+    add_impl_result_getters!(must_get_hname, get_hname, exists_hname, ScHname);
+    add_impl_result_getters!(must_get_hash, get_hash, exists_hash, ScHash);
+    add_impl_result_getters!(must_get_color, get_color, exists_color, ScColor);
+    add_impl_result_getters!(must_get_chain_id, get_chain_id, exists_chain_id, ScChainID);
+
+    // Arrays
+
+    /// Tries to get a result array. Panics if it can't find it, it has more than 'max_length' items,
+    /// it has holes, or an item has another type.
+    pub fn must_get_array<T : ArrayValue>(&self, result_name : &str, max_length : i32) -> Vec<T> {
+        if !T::array_exists_in(result_name, &self.results) {
+            panic!("{} not found", self.description(result_name));
+        }
+        self.get_array(result_name, max_length)
+    }
+
+    /// Tries to get a result array. Returns an empty Vec if it can't find it.
+    /// Panics if it has more than 'max_length' items, it has holes, or an item has another type.
+    pub fn get_array<T : ArrayValue>(&self, result_name : &str, max_length : i32) -> Vec<T> {
+        match params::read_array_items(&self.description(result_name), result_name, &self.results, max_length) {
+            Ok(items) => items,
+            Err(message) => panic!("{}", message),
+        }
+    }
+
+    add_impl_result_array_getters!(must_get_string_array, get_string_array, String);
+    add_impl_result_array_getters!(must_get_int64_array, get_int64_array, i64);
+    add_impl_result_array_getters!(must_get_bytes_array, get_bytes_array, Vec<u8>);
+    add_impl_result_array_getters!(must_get_bool_array, get_bool_array, bool);
+    add_impl_result_array_getters!(must_get_agent_id_array, get_agent_id_array, ScAgentID);
+    add_impl_result_array_getters!(must_get_address_array, get_address_array, ScAddress);
+    add_impl_result_array_getters!(must_get_request_id_array, get_request_id_array, ScRequestID);
+    add_impl_result_array_getters!(must_get_hname_array, get_hname_array, ScHname);
+    add_impl_result_array_getters!(must_get_hash_array, get_hash_array, ScHash);
+    add_impl_result_array_getters!(must_get_color_array, get_color_array, ScColor);
+    add_impl_result_array_getters!(must_get_chain_id_array, get_chain_id_array, ScChainID);
+
+    // Encoded types
+
+    /// Tries to get a result encoded with `codec::Encode`. Panics if it can't find it or it can't be decoded.
+    pub fn must_get_encoded<T : Decode>(&self, result_name : &str) -> T {
+        let bytes : Vec<u8> = self.must_get(result_name);
+        self.decode(result_name, &bytes)
+    }
+
+    /// Tries to get a result encoded with `codec::Encode`. Returns default value if it can't find it. Panics if it can't be decoded.
+    pub fn get_encoded<T : Decode + Default>(&self, result_name : &str) -> T {
+        let result = self.results.get_bytes(result_name);
+        if !result.exists() {
+            return T::default();
+        }
+        self.decode(result_name, &result.value())
+    }
+
+    fn decode<T : Decode>(&self, result_name : &str, bytes : &[u8]) -> T {
+        match T::decode(bytes) {
+            Ok(value) => value,
+            Err(error) => panic!("{} could not be decoded. Reason: {}", self.description(result_name), error),
+        }
+    }
+
+    // Structs
+
+    /// Reads a struct from the results. Panics if it can't find any of its fields.
+    pub fn must_get_struct<T : FromResults>(&self) -> T {
+        T::must_read_results(&self.results)
+    }
+
+    /// Reads a struct from the results. Uses default values for the fields it can't find.
+    pub fn get_struct<T : FromResults>(&self) -> T {
+        T::read_results(&self.results)
+    }
+}
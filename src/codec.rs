@@ -0,0 +1,625 @@
+use std::fmt;
+use wasmlib::*;
+use crate::values::MapValue;
+
+/// Length in bytes of an encoded ScAddress.
+pub const ADDRESS_LENGTH : usize = 33;
+/// Length in bytes of an encoded ScAgentID.
+pub const AGENT_ID_LENGTH : usize = 37;
+/// Length in bytes of an encoded ScChainID.
+pub const CHAIN_ID_LENGTH : usize = 33;
+/// Length in bytes of an encoded ScColor.
+pub const COLOR_LENGTH : usize = 32;
+/// Length in bytes of an encoded ScHash.
+pub const HASH_LENGTH : usize = 32;
+/// Length in bytes of an encoded ScHname.
+pub const HNAME_LENGTH : usize = 4;
+/// Length in bytes of an encoded ScRequestID.
+pub const REQUEST_ID_LENGTH : usize = 34;
+
+/// Describes why bytes could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Input ended before the value was complete.
+    UnexpectedEnd { needed : usize, remaining : usize },
+    /// Input has bytes left after the value was decoded.
+    TrailingBytes(usize),
+    /// Integer is too large for its type or is not encoded in its shortest form.
+    InvalidInteger,
+    /// Bool byte is neither 0 nor 1.
+    InvalidBool(u8),
+    /// Option tag is neither 0 nor 1.
+    InvalidOptionTag(u8),
+    /// Enum variant index does not exist.
+    InvalidVariant { type_name : &'static str, variant : u32 },
+    /// String is not valid UTF-8.
+    InvalidUtf8,
+    /// Value does not have the length of its type.
+    InvalidLength { expected : usize, actual : usize },
+    /// Address type byte is unknown, or not allowed for the type, e.g. a chain id which is not an alias address.
+    InvalidAddressType(u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd { needed, remaining } => write!(f, "unexpected end of input. Needed {} bytes, {} remaining", needed, remaining),
+            DecodeError::TrailingBytes(count) => write!(f, "{} unexpected bytes after value", count),
+            DecodeError::InvalidInteger => write!(f, "invalid integer"),
+            DecodeError::InvalidBool(byte) => write!(f, "invalid bool byte {}", byte),
+            DecodeError::InvalidOptionTag(tag) => write!(f, "invalid option tag {}", tag),
+            DecodeError::InvalidVariant { type_name, variant } => write!(f, "invalid variant {} for enum {}", variant, type_name),
+            DecodeError::InvalidUtf8 => write!(f, "invalid utf-8 string"),
+            DecodeError::InvalidLength { expected, actual } => write!(f, "invalid length {}. Expected: {}", actual, expected),
+            DecodeError::InvalidAddressType(address_type) => write!(f, "invalid address type {}", address_type),
+        }
+    }
+}
+
+/// Reads encoded values from a byte slice.
+pub struct Decoder<'a> {
+    bytes : &'a [u8],
+    position : usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Creates a decoder which reads 'bytes' from the start.
+    pub fn new(bytes : &'a [u8]) -> Decoder<'a> {
+        Decoder { bytes, position : 0 }
+    }
+
+    /// Returns the amount of bytes not read yet.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// Reads the next 'count' bytes.
+    pub fn read_bytes(&mut self, count : usize) -> Result<&'a [u8], DecodeError> {
+        let remaining = self.remaining();
+        if count > remaining {
+            return Err(DecodeError::UnexpectedEnd { needed : count, remaining });
+        }
+        let bytes = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    /// Reads the next byte.
+    pub fn read_byte(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    /// Reads an unsigned LEB128 integer. Fails if it is not in its shortest form.
+    pub fn read_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value : u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_byte()?;
+            let payload = u64::from(byte & 0x7f);
+            if shift == 63 && payload > 1 {
+                return Err(DecodeError::InvalidInteger);
+            }
+            value |= payload << shift;
+            if byte & 0x80 == 0 {
+                if byte == 0 && shift > 0 {
+                    return Err(DecodeError::InvalidInteger);
+                }
+                return Ok(value);
+            }
+            shift += 7;
+            if shift > 63 {
+                return Err(DecodeError::InvalidInteger);
+            }
+        }
+    }
+
+    /// Reads a length prefix.
+    pub fn read_length(&mut self) -> Result<usize, DecodeError> {
+        let length = self.read_varint()?;
+        if length > u32::MAX as u64 {
+            return Err(DecodeError::InvalidInteger);
+        }
+        Ok(length as usize)
+    }
+
+    /// Fails if there are bytes left.
+    pub fn finish(&self) -> Result<(), DecodeError> {
+        match self.remaining() {
+            0 => Ok(()),
+            remaining => Err(DecodeError::TrailingBytes(remaining)),
+        }
+    }
+}
+
+/// Writes an unsigned LEB128 integer.
+pub fn write_varint(value : u64, output : &mut Vec<u8>) {
+    let mut value = value;
+    while value >= 0x80 {
+        output.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+/// Writes a length prefix.
+pub fn write_length(length : usize, output : &mut Vec<u8>) {
+    write_varint(length as u64, output);
+}
+
+/// Defines how a value is converted into its compact binary representation. Use `#[derive(Encode)]` for structs and enums.
+pub trait Encode {
+    /// Appends the encoded value to 'output'.
+    fn encode_to(&self, output : &mut Vec<u8>);
+
+    /// Returns the encoded value.
+    fn encode(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.encode_to(&mut output);
+        output
+    }
+}
+
+/// Defines how a value is read from its compact binary representation. Use `#[derive(Decode)]` for structs and enums.
+pub trait Decode : Sized {
+    /// Reads the value from 'decoder'.
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError>;
+
+    /// Reads the value from 'bytes'. Fails if not all bytes are used.
+    fn decode(bytes : &[u8]) -> Result<Self, DecodeError> {
+        let mut decoder = Decoder::new(bytes);
+        let value = Self::decode_from(&mut decoder)?;
+        decoder.finish()?;
+        Ok(value)
+    }
+}
+
+/// Decodes 'bytes' stored under 'key'. Panics with the key and reason if it can't (the contract call stops).
+pub fn must_decode<T : Decode, TContext : ScBaseContext>(key : &str, bytes : &[u8], ctx : &TContext) -> T {
+    match T::decode(bytes) {
+        Ok(value) => value,
+        Err(error) => {
+            let message = format!("{} could not be decoded. Reason: {}", key, error);
+            ctx.panic(&message);
+            panic!("{}", message)
+        }
+    }
+}
+
+/// Sets the encoded 'value' under 'key' in 'mutable_map'.
+pub fn set_encoded_in<T : Encode>(key : &str, value : &T, mutable_map : &ScMutableMap) {
+    mutable_map.get_bytes(key).set_value(&value.encode());
+}
+
+/// Gets the value encoded under 'key' in 'immutable_map'. Panics with the key and reason if it can't find it or it can't be decoded,
+/// since encoded types have no default value.
+pub fn get_encoded_from<T : Decode, TContext : ScBaseContext>(key : &str, immutable_map : &ScImmutableMap, ctx : &TContext) -> T {
+    let bytes = immutable_map.get_bytes(key).value();
+    must_decode(key, &bytes, ctx)
+}
+
+/// Gets the value encoded under 'key' in 'immutable_map', for `MapValue` implementations, which have no context.
+/// Returns default value if it can't find it. Returns the reason if it can't be decoded, so the caller reports it through its context.
+pub fn try_get_encoded_value_from<T : Decode + Default>(key : &str, immutable_map : &ScImmutableMap) -> Result<T, DecodeError> {
+    let bytes = immutable_map.get_bytes(key);
+    if !bytes.exists() {
+        return Ok(T::default());
+    }
+    T::decode(&bytes.value())
+}
+
+/// Same as `try_get_encoded_value_from`. Panics with the key and reason if it can't be decoded (the contract call stops).
+/// Callers with a context use `values::must_get_from`, which reports the reason through it.
+pub fn get_encoded_value_from<T : Decode + Default>(key : &str, immutable_map : &ScImmutableMap) -> T {
+    match try_get_encoded_value_from(key, immutable_map) {
+        Ok(value) => value,
+        Err(error) => panic!("{} could not be decoded. Reason: {}", key, error),
+    }
+}
+
+/// Formats 'bytes' as lowercase hex, prefixed with "0x".
+pub fn to_hex(bytes : &[u8]) -> String {
+    let digits : String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("0x{}", digits)
+}
+
+/// Wraps a value of any type supported by the codec, so it can be stored as bytes through `MapValue`,
+/// e.g. in state collections. Types deriving MapValue don't need it. The type must implement Default, which is read when the value is missing.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Encoded<T>(pub T);
+
+impl<T : Encode + Decode + Default> MapValue for Encoded<T> {
+    fn set_in(&self, key : &str, mutable_map : &ScMutableMap) {
+        set_encoded_in(key, &self.0, mutable_map);
+    }
+
+    fn get_from(key : &str, immutable_map : &ScImmutableMap) -> Self {
+        Encoded(get_encoded_value_from(key, immutable_map))
+    }
+
+    fn try_get_from(key : &str, immutable_map : &ScImmutableMap) -> Result<Self, DecodeError> {
+        try_get_encoded_value_from(key, immutable_map).map(Encoded)
+    }
+
+    fn exists_in(key : &str, immutable_map : &ScImmutableMap) -> bool {
+        immutable_map.get_bytes(key).exists()
+    }
+
+    fn describe(&self) -> String {
+        to_hex(&self.0.encode())
+    }
+}
+
+// ---------------------------    Primitive types    -------------------------------------
+
+impl Encode for u8 {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        output.push(*self);
+    }
+}
+
+impl Decode for u8 {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        decoder.read_byte()
+    }
+}
+
+impl Encode for i8 {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        output.push(*self as u8);
+    }
+}
+
+impl Decode for i8 {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        Ok(decoder.read_byte()? as i8)
+    }
+}
+
+impl Encode for bool {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        output.push(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        match decoder.read_byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(DecodeError::InvalidBool(other)),
+        }
+    }
+}
+
+macro_rules! add_impl_unsigned {
+    ($t:ty) => {
+        /// Encoded as an unsigned LEB128 integer.
+        impl Encode for $t {
+            fn encode_to(&self, output : &mut Vec<u8>) {
+                write_varint(*self as u64, output);
+            }
+        }
+
+        impl Decode for $t {
+            fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+                let value = decoder.read_varint()?;
+                if value > <$t>::MAX as u64 {
+                    return Err(DecodeError::InvalidInteger);
+                }
+                Ok(value as $t)
+            }
+        }
+    };
+
+    ($t1:ty, $t2:ty, $t3:ty, $t4:ty) => {
+        add_impl_unsigned!($t1);
+        add_impl_unsigned!($t2);
+        add_impl_unsigned!($t3);
+        add_impl_unsigned!($t4);
+    };
+}
+
+macro_rules! add_impl_signed {
+    ($t:ty) => {
+        /// Encoded as a zigzag LEB128 integer, so small negative numbers stay small.
+        impl Encode for $t {
+            fn encode_to(&self, output : &mut Vec<u8>) {
+                let value = *self as i64;
+                write_varint(((value << 1) ^ (value >> 63)) as u64, output);
+            }
+        }
+
+        impl Decode for $t {
+            fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+                let zigzag = decoder.read_varint()?;
+                let value = ((zigzag >> 1) as i64) ^ -((zigzag & 1) as i64);
+                if value < <$t>::MIN as i64 || value > <$t>::MAX as i64 {
+                    return Err(DecodeError::InvalidInteger);
+                }
+                Ok(value as $t)
+            }
+        }
+    };
+
+    ($t1:ty, $t2:ty, $t3:ty, $t4:ty) => {
+        add_impl_signed!($t1);
+        add_impl_signed!($t2);
+        add_impl_signed!($t3);
+        add_impl_signed!($t4);
+    };
+}
+
+add_impl_unsigned!(u16, u32, u64, usize);
+add_impl_signed!(i16, i32, i64, isize);
+
+impl Encode for str {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        write_length(self.len(), output);
+        output.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        self.as_str().encode_to(output);
+    }
+}
+
+impl Decode for String {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        let length = decoder.read_length()?;
+        let bytes = decoder.read_bytes(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
+    }
+}
+
+// ---------------------------    Composite types    -------------------------------------
+
+impl<T : Encode> Encode for [T] {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        write_length(self.len(), output);
+        for item in self {
+            item.encode_to(output);
+        }
+    }
+}
+
+impl<T : Encode> Encode for Vec<T> {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        self.as_slice().encode_to(output);
+    }
+}
+
+impl<T : Decode> Decode for Vec<T> {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        let length = decoder.read_length()?;
+        // Every non zero-sized item takes at least one byte, so a longer length is invalid. Avoids huge allocations.
+        if std::mem::size_of::<T>() != 0 && length > decoder.remaining() {
+            return Err(DecodeError::UnexpectedEnd { needed : length, remaining : decoder.remaining() });
+        }
+        let mut items = Vec::with_capacity(length);
+        for _ in 0..length {
+            items.push(T::decode_from(decoder)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T : Encode> Encode for Option<T> {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        match self {
+            None => output.push(0),
+            Some(value) => {
+                output.push(1);
+                value.encode_to(output);
+            }
+        }
+    }
+}
+
+impl<T : Decode> Decode for Option<T> {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        match decoder.read_byte()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode_from(decoder)?)),
+            other => Err(DecodeError::InvalidOptionTag(other)),
+        }
+    }
+}
+
+impl<T : Encode + ?Sized> Encode for &T {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        (**self).encode_to(output);
+    }
+}
+
+impl<T : Encode + ?Sized> Encode for Box<T> {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        (**self).encode_to(output);
+    }
+}
+
+impl<T : Decode> Decode for Box<T> {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        Ok(Box::new(T::decode_from(decoder)?))
+    }
+}
+
+impl Encode for () {
+    fn encode_to(&self, _output : &mut Vec<u8>) {}
+}
+
+impl Decode for () {
+    fn decode_from(_decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        Ok(())
+    }
+}
+
+macro_rules! add_impl_tuple {
+    ($($name:ident),+) => {
+        /// Encoded as its items in order.
+        impl<$($name : Encode),+> Encode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode_to(&self, output : &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode_to(output);)+
+            }
+        }
+
+        impl<$($name : Decode),+> Decode for ($($name,)+) {
+            fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+                Ok(($($name::decode_from(decoder)?,)+))
+            }
+        }
+    };
+}
+
+add_impl_tuple!(A);
+add_impl_tuple!(A, B);
+add_impl_tuple!(A, B, C);
+add_impl_tuple!(A, B, C, D);
+add_impl_tuple!(A, B, C, D, E);
+add_impl_tuple!(A, B, C, D, E, F);
+
+// ---------------------------    ISCP types    -------------------------------------
+
+macro_rules! add_impl_fixed_length {
+    ($t:ty, $length:expr) => {
+        /// Encoded as its raw bytes, which have a fixed length.
+        impl Encode for $t {
+            fn encode_to(&self, output : &mut Vec<u8>) {
+                output.extend_from_slice(self.to_bytes());
+            }
+        }
+
+        impl Decode for $t {
+            fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+                Ok(<$t>::from_bytes(decoder.read_bytes($length)?))
+            }
+        }
+    };
+}
+
+add_impl_fixed_length!(ScAgentID, AGENT_ID_LENGTH);
+add_impl_fixed_length!(ScAddress, ADDRESS_LENGTH);
+add_impl_fixed_length!(ScRequestID, REQUEST_ID_LENGTH);
+add_impl_fixed_length!(ScHash, HASH_LENGTH);
+add_impl_fixed_length!(ScColor, COLOR_LENGTH);
+add_impl_fixed_length!(ScChainID, CHAIN_ID_LENGTH);
+
+/// Encoded as 4 little-endian bytes.
+impl Encode for ScHname {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        output.extend_from_slice(&self.0.to_le_bytes());
+    }
+}
+
+impl Decode for ScHname {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        let mut bytes = [0_u8; HNAME_LENGTH];
+        bytes.copy_from_slice(decoder.read_bytes(HNAME_LENGTH)?);
+        Ok(ScHname(u32::from_le_bytes(bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(value : u64) -> Vec<u8> {
+        let mut output = Vec::new();
+        write_varint(value, &mut output);
+        output
+    }
+
+    #[test]
+    fn varint_roundtrips_at_byte_boundaries() {
+        for value in [0, 1, 0x7f, 0x80, 0x3fff, 0x4000, u32::MAX as u64, u64::MAX] {
+            assert_eq!(Decoder::new(&varint(value)).read_varint(), Ok(value));
+        }
+        assert_eq!(varint(0x7f), vec![0x7f]);
+        assert_eq!(varint(0x80), vec![0x80, 0x01]);
+        assert_eq!(varint(u64::MAX).len(), 10);
+    }
+
+    #[test]
+    fn varint_rejects_non_shortest_forms() {
+        // 0 and 1 padded with a zero continuation byte.
+        assert_eq!(Decoder::new(&[0x80, 0x00]).read_varint(), Err(DecodeError::InvalidInteger));
+        assert_eq!(Decoder::new(&[0x81, 0x00]).read_varint(), Err(DecodeError::InvalidInteger));
+    }
+
+    #[test]
+    fn varint_rejects_overflows() {
+        let mut too_large = vec![0xff; 9];
+        too_large.push(0x02);
+        assert_eq!(Decoder::new(&too_large).read_varint(), Err(DecodeError::InvalidInteger));
+        let too_long = vec![0x80; 11];
+        assert_eq!(Decoder::new(&too_long).read_varint(), Err(DecodeError::InvalidInteger));
+        // u8 is a raw byte, not a varint, so the second byte of 256 is left over.
+        assert_eq!(u8::decode(&varint(256)), Err(DecodeError::TrailingBytes(1)));
+        assert_eq!(u16::decode(&varint(u16::MAX as u64 + 1)), Err(DecodeError::InvalidInteger));
+    }
+
+    #[test]
+    fn varint_rejects_truncated_input() {
+        assert_eq!(Decoder::new(&[0x80]).read_varint(), Err(DecodeError::UnexpectedEnd { needed : 1, remaining : 0 }));
+    }
+
+    #[test]
+    fn signed_integers_use_zigzag() {
+        assert_eq!(0_i64.encode(), vec![0]);
+        assert_eq!((-1_i64).encode(), vec![1]);
+        assert_eq!(1_i64.encode(), vec![2]);
+        assert_eq!((-2_i64).encode(), vec![3]);
+        assert_eq!(i64::MAX.encode(), varint(u64::MAX - 1));
+        assert_eq!(i64::MIN.encode(), varint(u64::MAX));
+        for value in [0, -1, 1, -64, 64, i64::MIN, i64::MAX] {
+            assert_eq!(i64::decode(&value.encode()), Ok(value));
+        }
+        assert_eq!(i16::decode(&(i16::MIN as i64 - 1).encode()), Err(DecodeError::InvalidInteger));
+        assert_eq!(i32::decode(&(i32::MAX as i64 + 1).encode()), Err(DecodeError::InvalidInteger));
+    }
+
+    #[test]
+    fn option_tags_are_zero_or_one() {
+        assert_eq!(None::<u8>.encode(), vec![0]);
+        assert_eq!(Some(7_u8).encode(), vec![1, 7]);
+        assert_eq!(Option::<u8>::decode(&[1, 7]), Ok(Some(7)));
+        assert_eq!(Option::<u8>::decode(&[2, 7]), Err(DecodeError::InvalidOptionTag(2)));
+        assert_eq!(bool::decode(&[2]), Err(DecodeError::InvalidBool(2)));
+    }
+
+    #[test]
+    fn vec_length_is_checked_before_allocating() {
+        // Claims 1000 items, but only 2 bytes follow.
+        let mut bytes = varint(1000);
+        bytes.extend_from_slice(&[1, 2]);
+        assert_eq!(Vec::<u8>::decode(&bytes), Err(DecodeError::UnexpectedEnd { needed : 1000, remaining : 2 }));
+        // Lengths above u32::MAX are rejected.
+        assert_eq!(Vec::<u8>::decode(&varint(u32::MAX as u64 + 1)), Err(DecodeError::InvalidInteger));
+        // Zero-sized items take no bytes, so any valid length is accepted.
+        assert_eq!(Vec::<()>::decode(&varint(3)), Ok(vec![(), (), ()]));
+        assert_eq!(Vec::<u16>::decode(&vec![300_u16, 1].encode()), Ok(vec![300, 1]));
+    }
+
+    #[test]
+    fn string_length_and_utf8_are_checked() {
+        assert_eq!(String::decode(&[5, b'a']), Err(DecodeError::UnexpectedEnd { needed : 5, remaining : 1 }));
+        assert_eq!(String::decode(&[2, 0xc3, 0x28]), Err(DecodeError::InvalidUtf8));
+        assert_eq!(String::decode(&"héllo".encode()), Ok("héllo".to_string()));
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() {
+        assert_eq!(u8::decode(&[1, 2]), Err(DecodeError::TrailingBytes(1)));
+        assert_eq!(<(u8, bool)>::decode(&[1, 0, 9, 9]), Err(DecodeError::TrailingBytes(2)));
+        assert_eq!(<(u8, bool)>::decode(&[1, 0]), Ok((1, false)));
+    }
+
+    #[test]
+    fn hname_is_little_endian() {
+        assert_eq!(ScHname(0x0102_0304).encode(), vec![4, 3, 2, 1]);
+        assert_eq!(ScHname::decode(&[4, 3, 2, 1]).map(|hname| hname.0), Ok(0x0102_0304));
+    }
+}
@@ -0,0 +1,215 @@
+use wasmlib::*;
+use crate::getter::Getter;
+use crate::params;
+use crate::values::{self, MapValue};
+
+mod iterable_map;
+mod map;
+mod priority_queue;
+mod queue;
+mod sorted_set;
+mod vec;
+
+pub use iterable_map::{IterableStateMap, IterableStateMapIter};
+pub use map::{Entry, StateMap};
+pub use priority_queue::StatePriorityQueue;
+pub use queue::{StateQueue, StateQueueIter};
+pub use sorted_set::{StateSortedSet, StateSortedSetIter};
+pub use vec::{StateVec, StateVecIter};
+
+// Every item of a collection is stored in its own nested map (a slot), under SLOT_VALUE.
+// Wasmlib can't delete single values, but it can clear maps, so removing an item clears its slot.
+const SLOT_VALUE : &str = "v";
+
+/// Reads the item stored in 'slot'. Returns None if it can't find it. Panics through 'ctx' if it can't be decoded.
+pub(crate) fn read_slot<V : MapValue, TContext : ScBaseContext>(slot : &ScImmutableMap, ctx : &TContext) -> Option<V> {
+    if !V::exists_in(SLOT_VALUE, slot) {
+        return None;
+    }
+    Some(values::must_get_from(SLOT_VALUE, slot, ctx))
+}
+
+/// Checks if 'slot' holds an item. Returns true if it does.
+pub(crate) fn slot_exists<V : MapValue>(slot : &ScImmutableMap) -> bool {
+    V::exists_in(SLOT_VALUE, slot)
+}
+
+/// Stores 'value' in 'slot'.
+pub(crate) fn write_slot<V : MapValue>(slot : &ScMutableMap, value : &V) {
+    value.set_in(SLOT_VALUE, slot);
+}
+
+/// Deletes the item stored in 'slot'.
+pub(crate) fn delete_slot(slot : &ScMutableMap) {
+    slot.clear();
+}
+
+// ---------------------------    Paging    -------------------------------------
+
+/// Name of the param with the position of the first item of a page. Defaults to 0.
+pub const PARAM_OFFSET : &str = "offset";
+/// Name of the param with the maximum amount of items of a page. Defaults to MAX_PAGE_LIMIT.
+pub const PARAM_LIMIT : &str = "limit";
+/// Name of the result with the items of a page. It is a map from the position of the item in the page ("0", "1", ...) to the item.
+pub const RESULT_ITEMS : &str = "items";
+/// Name of the result with the keys of a page of a map. It is a map from the position of the key in the page ("0", "1", ...) to the key.
+pub const RESULT_KEYS : &str = "keys";
+/// Name of the result with the amount of items of a page.
+pub const RESULT_COUNT : &str = "count";
+/// Name of the result with the amount of items in the whole collection.
+pub const RESULT_TOTAL : &str = "total";
+/// Maximum amount of items of a page, since views can not return unbounded data.
+pub const MAX_PAGE_LIMIT : i32 = 100;
+
+/// Slice of a collection, requested by a view or iterated in a contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Page {
+    /// Position of the first item.
+    pub offset : i32,
+    /// Maximum amount of items.
+    pub limit : i32,
+}
+
+impl Page {
+    /// Creates a page of view results. Panics if 'offset' is not between 0 and i32::MAX or 'limit' is not between 1 and MAX_PAGE_LIMIT.
+    pub fn new<TContext : ScBaseContext>(offset : i64, limit : i64, ctx : &TContext) -> Page {
+        ctx.require(offset >= 0 && offset <= i32::MAX as i64, &format!("Invalid {}: {}. It must be between 0 and {}.", PARAM_OFFSET, offset, i32::MAX));
+        ctx.require(limit >= 1 && limit <= MAX_PAGE_LIMIT as i64, &format!("Invalid {}: {}. It must be between 1 and {}.", PARAM_LIMIT, limit, MAX_PAGE_LIMIT));
+        Page { offset : offset as i32, limit : limit as i32 }
+    }
+
+    /// Creates a page iterated in a contract, which is only bounded by the length of the collection. Panics if 'offset' or 'limit' is negative.
+    pub(crate) fn iteration<TContext : ScBaseContext>(offset : i32, limit : i32, ctx : &TContext) -> Page {
+        ctx.require(offset >= 0, &format!("Invalid offset: {}. It must not be negative.", offset));
+        ctx.require(limit >= 0, &format!("Invalid limit: {}. It must not be negative.", limit));
+        Page { offset, limit }
+    }
+
+    /// Creates a page from params PARAM_OFFSET and PARAM_LIMIT.
+    pub fn from_params<TContext : Getter + ScBaseContext>(ctx : &TContext) -> Page {
+        let offset = params::get_int64(PARAM_OFFSET, ctx);
+        let limit = match params::exists_int64(PARAM_LIMIT, ctx) {
+            true => params::get_int64(PARAM_LIMIT, ctx),
+            false => MAX_PAGE_LIMIT as i64,
+        };
+        Page::new(offset, limit, ctx)
+    }
+
+    /// Returns the positions of the items of the page in a collection with 'total' items, as (first, end).
+    pub fn bounds(&self, total : i32) -> (i32, i32) {
+        let first = self.offset.min(total);
+        let end = first.saturating_add(self.limit).min(total);
+        (first, end)
+    }
+}
+
+/// Sets 'items' of a page as results, along with the amount of items in the page and in the whole collection.
+pub(crate) fn set_page_results<T : MapValue, TContext : ScBaseContext>(items : impl Iterator<Item = T>, total : i32, ctx : &TContext) {
+    let count = set_page_items(RESULT_ITEMS, items, ctx);
+    set_page_counts(count, total, ctx);
+}
+
+/// Sets 'items' of a page as the result 'result_name', a map from the position of the item in the page to the item. Returns the amount of items.
+pub(crate) fn set_page_items<T : MapValue, TContext : ScBaseContext>(result_name : &str, items : impl Iterator<Item = T>, ctx : &TContext) -> i32 {
+    let results_items = ctx.results().get_map(result_name);
+    let mut count : i32 = 0;
+    for item in items {
+        item.set_in(&count.to_string(), &results_items);
+        count += 1;
+    }
+    count
+}
+
+/// Sets the amount of items in a page and in the whole collection as results.
+pub(crate) fn set_page_counts<TContext : ScBaseContext>(count : i32, total : i32, ctx : &TContext) {
+    let results = ctx.results();
+    results.get_int64(RESULT_COUNT).set_value(count as i64);
+    results.get_int64(RESULT_TOTAL).set_value(total as i64);
+}
+
+/// Defines how a type is converted into the key of an item in a state collection.
+pub trait StateKey {
+    /// Returns the key as a string.
+    fn state_key(&self) -> String;
+}
+
+macro_rules! add_impl_state_key {
+    ($key_type:ty, $key:ident => $key_string:expr) => {
+        /// Adds support for using the type as key of state collections.
+        impl StateKey for $key_type {
+            fn state_key(&self) -> String {
+                let $key = self;
+                $key_string
+            }
+        }
+    };
+}
+
+// Primitive types
+add_impl_state_key!(String, key => key.clone());
+add_impl_state_key!(str, key => key.to_string());
+add_impl_state_key!(i8, key => key.to_string());
+add_impl_state_key!(i16, key => key.to_string());
+add_impl_state_key!(i32, key => key.to_string());
+add_impl_state_key!(i64, key => key.to_string());
+add_impl_state_key!(u8, key => key.to_string());
+add_impl_state_key!(u16, key => key.to_string());
+add_impl_state_key!(u32, key => key.to_string());
+add_impl_state_key!(u64, key => key.to_string());
+
+// ISCP types
+add_impl_state_key!(ScAgentID, key => key.to_string());
+add_impl_state_key!(ScAddress, key => key.to_string());
+add_impl_state_key!(ScRequestID, key => key.to_string());
+add_impl_state_key!(ScHname, key => key.0.to_string());
+add_impl_state_key!(ScHash, key => key.to_string());
+add_impl_state_key!(ScColor, key => key.to_string());
+add_impl_state_key!(ScChainID, key => key.to_string());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_keys_of_primitives_are_their_decimal_strings() {
+        assert_eq!("alice".state_key(), "alice");
+        assert_eq!("alice".to_string().state_key(), "alice");
+        assert_eq!((-5_i8).state_key(), "-5");
+        assert_eq!(i64::MIN.state_key(), "-9223372036854775808");
+        assert_eq!(u64::MAX.state_key(), "18446744073709551615");
+    }
+
+    #[test]
+    fn state_keys_of_integers_dont_depend_on_their_width() {
+        // A map keyed by u8 and one keyed by i64 store the same number under the same key.
+        assert_eq!(7_u8.state_key(), 7_i64.state_key());
+        assert_eq!(7_i32.state_key(), 7_u32.state_key());
+    }
+
+    #[test]
+    fn state_key_of_hname_is_its_decimal_value() {
+        assert_eq!(ScHname(0).state_key(), "0");
+        assert_eq!(ScHname(0x3c4b5e02).state_key(), "1011572226");
+        assert_ne!(ScHname(1).state_key(), ScHname(10).state_key());
+    }
+
+    #[test]
+    fn page_bounds_within_the_collection() {
+        assert_eq!(Page { offset : 0, limit : 10 }.bounds(25), (0, 10));
+        assert_eq!(Page { offset : 10, limit : 10 }.bounds(25), (10, 20));
+    }
+
+    #[test]
+    fn page_bounds_are_cut_at_the_end_of_the_collection() {
+        assert_eq!(Page { offset : 20, limit : 10 }.bounds(25), (20, 25));
+        assert_eq!(Page { offset : 25, limit : 10 }.bounds(25), (25, 25));
+        assert_eq!(Page { offset : 30, limit : 10 }.bounds(25), (25, 25));
+        assert_eq!(Page { offset : 0, limit : MAX_PAGE_LIMIT }.bounds(0), (0, 0));
+    }
+
+    #[test]
+    fn page_bounds_dont_overflow() {
+        assert_eq!(Page { offset : i32::MAX, limit : MAX_PAGE_LIMIT }.bounds(i32::MAX), (i32::MAX, i32::MAX));
+        assert_eq!(Page { offset : i32::MAX - 1, limit : MAX_PAGE_LIMIT }.bounds(i32::MAX), (i32::MAX - 1, i32::MAX));
+    }
+}
@@ -0,0 +1,317 @@
+use wasmlib::*;
+use crate::collections::{self, Page, StateKey, StateMap, StateVec, StateVecIter, RESULT_ITEMS, RESULT_KEYS};
+use crate::getter::Getter;
+use crate::state::StateReader;
+use crate::values::MapValue;
+
+/// Typed map persisted in the contract state which, unlike StateMap, can be enumerated.
+/// Next to the values, it keeps an index of its keys (a StateVec) and the position of each key in that index,
+/// so keys are removed in O(1) by moving the last key into the position of the removed one.
+/// # Sample:
+/// ```ignore
+/// let agents : IterableStateMap<ScAgentID, String> = IterableStateMap::new("agents");
+/// agents.insert(&ctx.caller(), &"alice".to_string(), ctx);
+/// for (agent_id, name) in agents.iter(0, 10, ctx) {
+///     // ...
+/// }
+/// ```
+pub struct IterableStateMap<K : StateKey + MapValue, V : MapValue> {
+    name : String,
+    values : StateMap<K, V>,
+    keys : StateVec<K>,
+    positions : StateMap<K, i64>,
+}
+
+impl<K : StateKey + MapValue, V : MapValue> IterableStateMap<K, V> {
+    /// Creates a map stored under 'name' in the contract state.
+    /// Its values, keys and positions of keys are stored under 'name.values', 'name.keys' and 'name.positions'.
+    pub fn new(name : &str) -> IterableStateMap<K, V> {
+        IterableStateMap {
+            name : name.to_string(),
+            values : StateMap::new(&format!("{}.values", name)),
+            keys : StateVec::new(&format!("{}.keys", name)),
+            positions : StateMap::new(&format!("{}.positions", name)),
+        }
+    }
+
+    /// Returns the name of the map in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the amount of keys.
+    pub fn len<TContext : StateReader>(&self, ctx : &TContext) -> i32 {
+        self.keys.len(ctx)
+    }
+
+    /// Checks if there are no keys. Returns true if it is empty.
+    pub fn is_empty<TContext : StateReader>(&self, ctx : &TContext) -> bool {
+        self.keys.is_empty(ctx)
+    }
+
+    /// Tries to get the value of 'key'. Returns None if it can't find it.
+    pub fn get<TContext : StateReader>(&self, key : &K, ctx : &TContext) -> Option<V> {
+        self.values.get(key, ctx)
+    }
+
+    /// Tries to get the value of 'key'. Panics if it can't find it.
+    pub fn must_get<TContext : StateReader>(&self, key : &K, ctx : &TContext) -> V {
+        match self.get(key, ctx) {
+            Some(value) => value,
+            None => {
+                let message = format!("key {} not found in map {}", key.state_key(), self.name);
+                ctx.panic(&message);
+                panic!("{}", message)
+            }
+        }
+    }
+
+    /// Checks if 'key' has a value. Returns true if it has.
+    pub fn contains_key<TContext : StateReader>(&self, key : &K, ctx : &TContext) -> bool {
+        self.positions.contains_key(key, ctx)
+    }
+
+    fn index<'a>(&'a self, ctx : &'a ScFuncContext) -> StateIndex<'a, K> {
+        StateIndex { keys : &self.keys, positions : &self.positions, ctx }
+    }
+
+    /// Sets the value of 'key'. New keys are added to the end of the index.
+    pub fn insert(&self, key : &K, value : &V, ctx : &ScFuncContext) {
+        self.index(ctx).add(key);
+        self.values.insert(key, value, ctx);
+    }
+
+    /// Removes 'key' from the map. Returns its value, or None if it can't find it.
+    /// The last key of the index takes the position of the removed one, so the order of keys is not kept.
+    pub fn remove(&self, key : &K, ctx : &ScFuncContext) -> Option<V> {
+        if !self.index(ctx).remove(key) {
+            return None;
+        }
+        self.values.remove(key, ctx)
+    }
+
+    /// Iterates over at most 'limit' keys of the index and their values, starting at 'offset'.
+    pub fn iter<'a, TContext : StateReader>(&'a self, offset : i32, limit : i32, ctx : &'a TContext) -> IterableStateMapIter<'a, K, V, TContext> {
+        IterableStateMapIter { map : self, keys : self.keys.iter(offset, limit, ctx), ctx }
+    }
+
+    /// Iterates over at most 'limit' keys of the index, starting at 'offset'.
+    pub fn keys<'a, TContext : StateReader>(&'a self, offset : i32, limit : i32, ctx : &'a TContext) -> StateVecIter<'a, K, TContext> {
+        self.keys.iter(offset, limit, ctx)
+    }
+
+    /// Sets the page of keys and values requested through params PARAM_OFFSET and PARAM_LIMIT as results. Meant for views.
+    /// Keys are set in RESULT_KEYS and their values in RESULT_ITEMS, at the same positions.
+    pub fn set_page_results<TContext : StateReader + Getter>(&self, ctx : &TContext) {
+        let page = Page::from_params(ctx);
+        let (keys, values) : (Vec<K>, Vec<V>) = self.iter(page.offset, page.limit, ctx).unzip();
+        let count = collections::set_page_items(RESULT_KEYS, keys.into_iter(), ctx);
+        collections::set_page_items(RESULT_ITEMS, values.into_iter(), ctx);
+        collections::set_page_counts(count, self.len(ctx), ctx);
+    }
+}
+
+/// Returns the position of the key which takes 'position' when it is removed from an index of 'length' keys, or None if it was the last one.
+fn moved_position(position : i32, length : i32) -> Option<i32> {
+    let last_position = length - 1;
+    match position == last_position {
+        true => None,
+        false => Some(last_position),
+    }
+}
+
+/// Storage of the index of keys and their positions, so its updates run the same over the contract state and in memory.
+trait KeyIndex<K> {
+    /// Returns the amount of keys.
+    fn len(&self) -> i32;
+
+    /// Returns the key at 'position'.
+    fn key(&self, position : i32) -> K;
+
+    /// Checks if 'key' is in the index. Returns true if it is.
+    fn contains(&self, key : &K) -> bool;
+
+    /// Sets the position of 'key'.
+    fn set_position(&mut self, key : &K, position : i32);
+
+    /// Removes the position of 'key' and returns it. Returns None if it can't find it.
+    fn remove_position(&mut self, key : &K) -> Option<i32>;
+
+    /// Adds 'key' after the last one.
+    fn push_key(&mut self, key : &K);
+
+    /// Removes the key at 'position', moving the last key into it.
+    fn swap_remove_key(&mut self, position : i32);
+
+    /// Adds 'key' to the end of the index, unless it is in it already.
+    fn add(&mut self, key : &K) {
+        if !self.contains(key) {
+            let position = self.len();
+            self.set_position(key, position);
+            self.push_key(key);
+        }
+    }
+
+    /// Removes 'key' from the index. The last key takes its position. Returns false if it can't find it.
+    fn remove(&mut self, key : &K) -> bool {
+        let position = match self.remove_position(key) {
+            Some(position) => position,
+            None => return false,
+        };
+        if let Some(last_position) = moved_position(position, self.len()) {
+            let last_key = self.key(last_position);
+            self.set_position(&last_key, position);
+        }
+        self.swap_remove_key(position);
+        true
+    }
+}
+
+/// Index of an IterableStateMap, kept in a StateVec of keys and a StateMap of their positions.
+struct StateIndex<'a, K : StateKey + MapValue> {
+    keys : &'a StateVec<K>,
+    positions : &'a StateMap<K, i64>,
+    ctx : &'a ScFuncContext,
+}
+
+impl<'a, K : StateKey + MapValue> KeyIndex<K> for StateIndex<'a, K> {
+    fn len(&self) -> i32 {
+        self.keys.len(self.ctx)
+    }
+
+    fn key(&self, position : i32) -> K {
+        self.keys.must_get(position, self.ctx)
+    }
+
+    fn contains(&self, key : &K) -> bool {
+        self.positions.contains_key(key, self.ctx)
+    }
+
+    fn set_position(&mut self, key : &K, position : i32) {
+        self.positions.insert(key, &(position as i64), self.ctx);
+    }
+
+    fn remove_position(&mut self, key : &K) -> Option<i32> {
+        self.positions.remove(key, self.ctx).map(|position| position as i32)
+    }
+
+    fn push_key(&mut self, key : &K) {
+        self.keys.push(key, self.ctx);
+    }
+
+    fn swap_remove_key(&mut self, position : i32) {
+        self.keys.swap_remove(position, self.ctx);
+    }
+}
+
+/// Iterator over a bounded range of keys of an IterableStateMap and their values.
+pub struct IterableStateMapIter<'a, K : StateKey + MapValue, V : MapValue, TContext : StateReader> {
+    map : &'a IterableStateMap<K, V>,
+    keys : StateVecIter<'a, K, TContext>,
+    ctx : &'a TContext,
+}
+
+impl<'a, K : StateKey + MapValue, V : MapValue, TContext : StateReader> Iterator for IterableStateMapIter<'a, K, V, TContext> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let key = self.keys.next()?;
+        let value = self.map.must_get(&key, self.ctx);
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Index of keys and their positions in memory.
+    struct Index {
+        keys : Vec<&'static str>,
+        positions : HashMap<&'static str, i32>,
+    }
+
+    impl KeyIndex<&'static str> for Index {
+        fn len(&self) -> i32 {
+            self.keys.len() as i32
+        }
+
+        fn key(&self, position : i32) -> &'static str {
+            self.keys[position as usize]
+        }
+
+        fn contains(&self, key : &&'static str) -> bool {
+            self.positions.contains_key(key)
+        }
+
+        fn set_position(&mut self, key : &&'static str, position : i32) {
+            self.positions.insert(key, position);
+        }
+
+        fn remove_position(&mut self, key : &&'static str) -> Option<i32> {
+            self.positions.remove(key)
+        }
+
+        fn push_key(&mut self, key : &&'static str) {
+            self.keys.push(key);
+        }
+
+        fn swap_remove_key(&mut self, position : i32) {
+            self.keys.swap_remove(position as usize);
+        }
+    }
+
+    impl Index {
+        fn new(keys : &[&'static str]) -> Index {
+            let mut index = Index { keys : Vec::new(), positions : HashMap::new() };
+            for key in keys {
+                index.add(key);
+            }
+            index
+        }
+
+        fn assert_consistent(&self) {
+            assert_eq!(self.keys.len(), self.positions.len());
+            for (position, key) in self.keys.iter().enumerate() {
+                assert_eq!(self.positions[key], position as i32, "position of {}", key);
+            }
+        }
+    }
+
+    #[test]
+    fn removing_the_last_key_moves_nothing() {
+        assert_eq!(moved_position(4, 5), None);
+        assert_eq!(moved_position(0, 1), None);
+    }
+
+    #[test]
+    fn removing_another_key_moves_the_last_one() {
+        assert_eq!(moved_position(0, 5), Some(4));
+        assert_eq!(moved_position(3, 5), Some(4));
+    }
+
+    #[test]
+    fn positions_stay_consistent_after_removals() {
+        let mut index = Index::new(&["a", "b", "c", "d", "e", "a"]);
+        index.assert_consistent();
+        assert_eq!(index.keys, vec!["a", "b", "c", "d", "e"]);
+        assert!(index.remove(&"b"));
+        assert!(!index.remove(&"b"));
+        index.assert_consistent();
+        assert_eq!(index.keys, vec!["a", "e", "c", "d"]);
+        index.remove(&"d");
+        index.assert_consistent();
+        index.remove(&"a");
+        index.assert_consistent();
+        assert_eq!(index.keys, vec!["c", "e"]);
+        index.remove(&"e");
+        index.remove(&"c");
+        index.assert_consistent();
+        assert!(index.keys.is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+use std::marker::PhantomData;
+use wasmlib::*;
+use crate::collections::{self, StateKey};
+use crate::math::SafeMath;
+use crate::state::StateReader;
+use crate::values::MapValue;
+
+/// Typed map persisted in the contract state, under the nested map 'name'.
+/// # Sample:
+/// ```ignore
+/// let balances : StateMap<ScAgentID, i64> = StateMap::new("balances");
+/// balances.entry(&ctx.caller(), ctx).safe_add(&10);
+/// let balance : i64 = balances.must_get(&ctx.caller(), ctx);
+/// ```
+pub struct StateMap<K : StateKey + ?Sized, V : MapValue> {
+    name : String,
+    _types : PhantomData<(Box<K>, V)>,
+}
+
+impl<K : StateKey + ?Sized, V : MapValue> StateMap<K, V> {
+    /// Creates a map stored under 'name' in the contract state.
+    pub fn new(name : &str) -> StateMap<K, V> {
+        StateMap { name : name.to_string(), _types : PhantomData }
+    }
+
+    /// Returns the name of the map in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn immutable_map<TContext : StateReader>(&self, ctx : &TContext) -> ScImmutableMap {
+        ctx.immutable_state().get_map(self.name.as_str())
+    }
+
+    fn mutable_map(&self, ctx : &ScFuncContext) -> ScMutableMap {
+        ctx.state().get_map(self.name.as_str())
+    }
+
+    /// Tries to get the value of 'key'. Returns None if it can't find it.
+    pub fn get<TContext : StateReader>(&self, key : &K, ctx : &TContext) -> Option<V> {
+        collections::read_slot(&self.immutable_map(ctx).get_map(key.state_key().as_str()), ctx)
+    }
+
+    /// Tries to get the value of 'key'. Panics if it can't find it.
+    pub fn must_get<TContext : StateReader>(&self, key : &K, ctx : &TContext) -> V {
+        match self.get(key, ctx) {
+            Some(value) => value,
+            None => {
+                let message = format!("key {} not found in map {}", key.state_key(), self.name);
+                ctx.panic(&message);
+                panic!("{}", message)
+            }
+        }
+    }
+
+    /// Checks if 'key' has a value. Returns true if it has.
+    pub fn contains_key<TContext : StateReader>(&self, key : &K, ctx : &TContext) -> bool {
+        collections::slot_exists::<V>(&self.immutable_map(ctx).get_map(key.state_key().as_str()))
+    }
+
+    /// Sets the value of 'key'.
+    pub fn insert(&self, key : &K, value : &V, ctx : &ScFuncContext) {
+        collections::write_slot(&self.mutable_map(ctx).get_map(key.state_key().as_str()), value);
+    }
+
+    /// Removes 'key' from the map. Returns its value, or None if it can't find it.
+    pub fn remove(&self, key : &K, ctx : &ScFuncContext) -> Option<V> {
+        let slot = self.mutable_map(ctx).get_map(key.state_key().as_str());
+        let value = collections::read_slot(&slot.immutable(), ctx);
+        if value.is_some() {
+            collections::delete_slot(&slot);
+        }
+        value
+    }
+
+    /// Gets the entry of 'key' for in-place updates.
+    pub fn entry<'a>(&'a self, key : &K, ctx : &'a ScFuncContext) -> Entry<'a, K, V> {
+        Entry { map : self, key : key.state_key(), ctx }
+    }
+}
+
+/// Entry of a StateMap, used to update a value in place.
+pub struct Entry<'a, K : StateKey + ?Sized, V : MapValue> {
+    map : &'a StateMap<K, V>,
+    key : String,
+    ctx : &'a ScFuncContext,
+}
+
+impl<'a, K : StateKey + ?Sized, V : MapValue> Entry<'a, K, V> {
+    /// Returns the current value. Returns None if there is none.
+    pub fn get(&self) -> Option<V> {
+        collections::read_slot(&self.slot().immutable(), self.ctx)
+    }
+
+    fn slot(&self) -> ScMutableMap {
+        self.map.mutable_map(self.ctx).get_map(self.key.as_str())
+    }
+
+    fn set(&self, value : &V) {
+        collections::write_slot(&self.slot(), value);
+    }
+
+    /// Sets 'value' if there is no value yet. Returns the resulting value.
+    pub fn or_insert(self, value : V) -> V {
+        match self.get() {
+            Some(current_value) => current_value,
+            None => {
+                self.set(&value);
+                value
+            }
+        }
+    }
+
+    /// Sets the default value if there is no value yet. Returns the resulting value.
+    pub fn or_default(self) -> V where V : Default {
+        self.or_insert(V::default())
+    }
+
+    /// Modifies the value with 'modify' if there is one.
+    pub fn and_modify<F : FnOnce(&mut V)>(self, modify : F) -> Self {
+        if let Some(mut value) = self.get() {
+            modify(&mut value);
+            self.set(&value);
+        }
+        self
+    }
+
+    /// Sets the value returned by 'update', which receives the current value, if any. Returns the new value.
+    pub fn update<F : FnOnce(Option<V>) -> V>(self, update : F) -> V {
+        let value = update(self.get());
+        self.set(&value);
+        value
+    }
+}
+
+impl<'a, K : StateKey + ?Sized, V : MapValue + SafeMath + Default> Entry<'a, K, V> {
+    /// Adds 'amount' to the value, which starts at default if there is none. Panics on under/overflows (the contract call stops).
+    pub fn safe_add(self, amount : &V) -> V {
+        let ctx = self.ctx;
+        self.update(|value| value.unwrap_or_default().safe_add(amount, ctx))
+    }
+
+    /// Subtracts 'amount' from the value, which starts at default if there is none. Panics on under/overflows (the contract call stops).
+    pub fn safe_sub(self, amount : &V) -> V {
+        let ctx = self.ctx;
+        self.update(|value| value.unwrap_or_default().safe_sub(amount, ctx))
+    }
+}
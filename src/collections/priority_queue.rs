@@ -0,0 +1,244 @@
+use wasmlib::*;
+use crate::codec::{self, Decode, Encode};
+use crate::collections::StateVec;
+use crate::state::StateReader;
+
+/// Priority queue persisted in the contract state, under the nested map 'name'. Items are stored with the crate's codec.
+/// Items are kept in a binary max-heap over a StateVec, so pushing and popping read and write O(log n) items,
+/// i.e. O(log n) host calls, and the highest item is always read in O(1).
+/// # Sample:
+/// ```ignore
+/// let orders : StatePriorityQueue<(i64, ScRequestID)> = StatePriorityQueue::new("orders");
+/// orders.push((price, ctx.request_id()), ctx);
+/// let best_order : Option<(i64, ScRequestID)> = orders.pop(ctx);
+/// ```
+pub struct StatePriorityQueue<T : Encode + Decode + Ord> {
+    heap : StateVec<Vec<u8>>,
+    item_type : std::marker::PhantomData<T>,
+}
+
+impl<T : Encode + Decode + Ord> StatePriorityQueue<T> {
+    /// Creates a priority queue stored under 'name' in the contract state.
+    pub fn new(name : &str) -> StatePriorityQueue<T> {
+        StatePriorityQueue { heap : StateVec::new(name), item_type : std::marker::PhantomData }
+    }
+
+    /// Returns the name of the priority queue in the contract state.
+    pub fn name(&self) -> &str {
+        self.heap.name()
+    }
+
+    /// Returns the amount of items.
+    pub fn len<TContext : StateReader>(&self, ctx : &TContext) -> i32 {
+        self.heap.len(ctx)
+    }
+
+    /// Checks if there are no items. Returns true if it is empty.
+    pub fn is_empty<TContext : StateReader>(&self, ctx : &TContext) -> bool {
+        self.heap.is_empty(ctx)
+    }
+
+    /// Returns the highest item without removing it. Returns None if it is empty.
+    pub fn peek<TContext : StateReader>(&self, ctx : &TContext) -> Option<T> {
+        self.heap.get(0, ctx).map(|bytes| codec::must_decode(self.name(), &bytes, ctx))
+    }
+
+    /// Adds 'item' to the queue.
+    pub fn push(&self, item : T, ctx : &ScFuncContext) {
+        StateHeap { heap : &self.heap, ctx, item_type : std::marker::PhantomData }.push(item);
+    }
+
+    /// Removes the highest item and returns it. Returns None if it is empty.
+    pub fn pop(&self, ctx : &ScFuncContext) -> Option<T> {
+        StateHeap { heap : &self.heap, ctx, item_type : std::marker::PhantomData }.pop()
+    }
+
+    /// Removes every item.
+    pub fn clear(&self, ctx : &ScFuncContext) {
+        self.heap.clear(ctx);
+    }
+
+    /// Iterates over at most 'limit' items, starting at 'offset', in heap order (not sorted). Only the first item is known to be the highest.
+    pub fn iter<'a, TContext : StateReader>(&'a self, offset : i32, limit : i32, ctx : &'a TContext) -> impl Iterator<Item = T> + 'a {
+        self.heap.iter(offset, limit, ctx).map(move |bytes| codec::must_decode(self.name(), &bytes, ctx))
+    }
+}
+
+/// Storage of the items of a binary max-heap, so its algorithms run the same over the contract state and in memory.
+trait Heap<T : Ord> {
+    /// Returns the amount of items.
+    fn len(&self) -> i32;
+
+    /// Returns the item at 'index'.
+    fn get(&self, index : i32) -> T;
+
+    /// Writes 'item' at 'index', which is either an existing position or the end of the heap.
+    fn write(&mut self, index : i32, item : T);
+
+    /// Removes the item at the end of the heap and returns it. Returns None if it is empty.
+    fn pop_last(&mut self) -> Option<T>;
+
+    /// Adds 'item' to the heap.
+    fn push(&mut self, item : T) {
+        // Moves the item up while it is higher than its parent.
+        let mut index = self.len();
+        while index > 0 {
+            let parent_index = (index - 1) / 2;
+            let parent = self.get(parent_index);
+            if parent >= item {
+                break;
+            }
+            self.write(index, parent);
+            index = parent_index;
+        }
+        self.write(index, item);
+    }
+
+    /// Removes the highest item and returns it. Returns None if it is empty.
+    fn pop(&mut self) -> Option<T> {
+        let last = self.pop_last()?;
+        let length = self.len();
+        if length == 0 {
+            return Some(last);
+        }
+        let highest = self.get(0);
+
+        // Moves the last item down from the top while it is lower than its highest child.
+        let mut index = 0;
+        loop {
+            let left_index = 2 * index + 1;
+            if left_index >= length {
+                break;
+            }
+            let right_index = left_index + 1;
+            let mut child_index = left_index;
+            let mut child = self.get(left_index);
+            if right_index < length {
+                let right_child = self.get(right_index);
+                if right_child > child {
+                    child_index = right_index;
+                    child = right_child;
+                }
+            }
+            if last >= child {
+                break;
+            }
+            self.write(index, child);
+            index = child_index;
+        }
+        self.write(index, last);
+        Some(highest)
+    }
+}
+
+/// Heap of a StatePriorityQueue, kept in a StateVec.
+struct StateHeap<'a, T : Encode + Decode + Ord> {
+    heap : &'a StateVec<Vec<u8>>,
+    ctx : &'a ScFuncContext,
+    item_type : std::marker::PhantomData<T>,
+}
+
+impl<'a, T : Encode + Decode + Ord> Heap<T> for StateHeap<'a, T> {
+    fn len(&self) -> i32 {
+        self.heap.len(self.ctx)
+    }
+
+    fn get(&self, index : i32) -> T {
+        codec::must_decode(self.heap.name(), &self.heap.must_get(index, self.ctx), self.ctx)
+    }
+
+    fn write(&mut self, index : i32, item : T) {
+        if index == self.heap.len(self.ctx) {
+            self.heap.push(&item.encode(), self.ctx);
+        } else {
+            self.heap.set(index, &item.encode(), self.ctx);
+        }
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        self.heap.pop(self.ctx).map(|bytes| codec::must_decode(self.heap.name(), &bytes, self.ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl<T : Ord + Clone> Heap<T> for Vec<T> {
+        fn len(&self) -> i32 {
+            Vec::len(self) as i32
+        }
+
+        fn get(&self, index : i32) -> T {
+            self[index as usize].clone()
+        }
+
+        fn write(&mut self, index : i32, item : T) {
+            match index as usize == Vec::len(self) {
+                true => Vec::push(self, item),
+                false => self[index as usize] = item,
+            }
+        }
+
+        fn pop_last(&mut self) -> Option<T> {
+            Vec::pop(self)
+        }
+    }
+
+    fn assert_heap_ordered(heap : &[i64]) {
+        for index in 1..heap.len() {
+            assert!(heap[(index - 1) / 2] >= heap[index], "item {} at {} is higher than its parent in {:?}", heap[index], index, heap);
+        }
+    }
+
+    /// Deterministic sequence of items with repeats.
+    fn items(count : i64) -> Vec<i64> {
+        (0..count).map(|index| (index * 7_919) % 101).collect()
+    }
+
+    #[test]
+    fn pops_items_from_highest_to_lowest() {
+        let mut heap : Vec<i64> = Vec::new();
+        for item in items(200) {
+            Heap::push(&mut heap, item);
+            assert_heap_ordered(&heap);
+        }
+        let mut expected = items(200);
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        let mut popped = Vec::new();
+        while let Some(item) = Heap::pop(&mut heap) {
+            assert_heap_ordered(&heap);
+            popped.push(item);
+        }
+        assert_eq!(popped, expected);
+    }
+
+    #[test]
+    fn stays_ordered_when_pushes_and_pops_interleave() {
+        let mut heap : Vec<i64> = Vec::new();
+        let mut expected : Vec<i64> = Vec::new();
+        for (index, item) in items(300).into_iter().enumerate() {
+            Heap::push(&mut heap, item);
+            expected.push(item);
+            if index % 3 == 2 {
+                expected.sort_unstable();
+                assert_eq!(Heap::pop(&mut heap), expected.pop());
+                assert_heap_ordered(&heap);
+            }
+        }
+        expected.sort_unstable();
+        while let Some(item) = Heap::pop(&mut heap) {
+            assert_eq!(Some(item), expected.pop());
+        }
+        assert!(expected.is_empty());
+    }
+
+    #[test]
+    fn pop_of_empty_heap_returns_none() {
+        let mut heap : Vec<i64> = Vec::new();
+        assert_eq!(Heap::pop(&mut heap), None);
+        Heap::push(&mut heap, 5);
+        assert_eq!(Heap::pop(&mut heap), Some(5));
+        assert_eq!(Heap::pop(&mut heap), None);
+    }
+}
@@ -0,0 +1,334 @@
+use std::marker::PhantomData;
+use wasmlib::*;
+use crate::collections::{self, Page};
+use crate::getter::Getter;
+use crate::state::StateReader;
+use crate::values::MapValue;
+
+/// Name of the position of the first item, inside the nested map of the queue.
+const KEY_HEAD : &str = "head";
+/// Name of the position after the last item, inside the nested map of the queue.
+const KEY_TAIL : &str = "tail";
+/// Name of the map of items by position, inside the nested map of the queue.
+const KEY_ITEMS : &str = "items";
+
+/// Double-ended queue persisted in the contract state, under the nested map 'name'.
+/// Items are kept by position between a head and a tail index, so every operation takes O(1) host calls.
+/// Slots of removed items are deleted, so the state only holds the items in the queue.
+/// # Sample:
+/// ```ignore
+/// let withdrawals : StateQueue<ScAgentID> = StateQueue::new("withdrawals");
+/// withdrawals.push_back(&ctx.caller(), ctx);
+/// let next_withdrawal : Option<ScAgentID> = withdrawals.pop_front(ctx);
+/// ```
+pub struct StateQueue<T : MapValue> {
+    name : String,
+    _type : PhantomData<T>,
+}
+
+impl<T : MapValue> StateQueue<T> {
+    /// Creates a queue stored under 'name' in the contract state.
+    pub fn new(name : &str) -> StateQueue<T> {
+        StateQueue { name : name.to_string(), _type : PhantomData }
+    }
+
+    /// Returns the name of the queue in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn immutable_map<TContext : StateReader>(&self, ctx : &TContext) -> ScImmutableMap {
+        ctx.immutable_state().get_map(self.name.as_str())
+    }
+
+    fn mutable_map(&self, ctx : &ScFuncContext) -> ScMutableMap {
+        ctx.state().get_map(self.name.as_str())
+    }
+
+    fn bounds<TContext : StateReader>(&self, ctx : &TContext) -> Bounds {
+        let map = self.immutable_map(ctx);
+        Bounds { head : map.get_int64(KEY_HEAD).value(), tail : map.get_int64(KEY_TAIL).value() }
+    }
+
+    fn slot(&self, position : i64, ctx : &ScFuncContext) -> ScMutableMap {
+        self.mutable_map(ctx).get_map(KEY_ITEMS).get_map(position.to_string().as_str())
+    }
+
+    fn read<TContext : StateReader>(&self, position : i64, ctx : &TContext) -> Option<T> {
+        collections::read_slot(&self.immutable_map(ctx).get_map(KEY_ITEMS).get_map(position.to_string().as_str()), ctx)
+    }
+
+    fn set_bounds(&self, bounds : Bounds, ctx : &ScFuncContext) {
+        let map = self.mutable_map(ctx);
+        map.get_int64(KEY_HEAD).set_value(bounds.head);
+        map.get_int64(KEY_TAIL).set_value(bounds.tail);
+    }
+
+    /// Returns the amount of items.
+    pub fn len<TContext : StateReader>(&self, ctx : &TContext) -> i32 {
+        self.bounds(ctx).len()
+    }
+
+    /// Checks if there are no items. Returns true if it is empty.
+    pub fn is_empty<TContext : StateReader>(&self, ctx : &TContext) -> bool {
+        self.len(ctx) == 0
+    }
+
+    /// Returns the first item without removing it. Returns None if it is empty.
+    pub fn peek<TContext : StateReader>(&self, ctx : &TContext) -> Option<T> {
+        self.read(self.bounds(ctx).front()?, ctx)
+    }
+
+    /// Returns the last item without removing it. Returns None if it is empty.
+    pub fn peek_back<TContext : StateReader>(&self, ctx : &TContext) -> Option<T> {
+        self.read(self.bounds(ctx).back()?, ctx)
+    }
+
+    /// Appends 'item' after the last item.
+    pub fn push_back(&self, item : &T, ctx : &ScFuncContext) {
+        let bounds = self.bounds(ctx);
+        ctx.require(!bounds.is_full(), &format!("queue {} is full", self.name));
+        let (position, bounds) = bounds.push_back();
+        collections::write_slot(&self.slot(position, ctx), item);
+        self.set_bounds(bounds, ctx);
+    }
+
+    /// Prepends 'item' before the first item.
+    pub fn push_front(&self, item : &T, ctx : &ScFuncContext) {
+        let bounds = self.bounds(ctx);
+        ctx.require(!bounds.is_full(), &format!("queue {} is full", self.name));
+        let (position, bounds) = bounds.push_front();
+        collections::write_slot(&self.slot(position, ctx), item);
+        self.set_bounds(bounds, ctx);
+    }
+
+    /// Removes the first item and returns it. Returns None if it is empty.
+    pub fn pop_front(&self, ctx : &ScFuncContext) -> Option<T> {
+        let (position, bounds) = self.bounds(ctx).pop_front()?;
+        let item = self.read(position, ctx);
+        collections::delete_slot(&self.slot(position, ctx));
+        self.set_bounds(bounds, ctx);
+        item
+    }
+
+    /// Removes the last item and returns it. Returns None if it is empty.
+    pub fn pop_back(&self, ctx : &ScFuncContext) -> Option<T> {
+        let (position, bounds) = self.bounds(ctx).pop_back()?;
+        let item = self.read(position, ctx);
+        collections::delete_slot(&self.slot(position, ctx));
+        self.set_bounds(bounds, ctx);
+        item
+    }
+
+    /// Removes every item.
+    pub fn clear(&self, ctx : &ScFuncContext) {
+        self.mutable_map(ctx).clear();
+    }
+
+    /// Iterates over at most 'limit' items, starting at the item in position 'offset' from the front. Does not remove them.
+    pub fn iter<'a, TContext : StateReader>(&'a self, offset : i32, limit : i32, ctx : &'a TContext) -> StateQueueIter<'a, T, TContext> {
+        let page = Page::iteration(offset, limit, ctx);
+        self.page_iter(page, ctx)
+    }
+
+    fn page_iter<'a, TContext : StateReader>(&'a self, page : Page, ctx : &'a TContext) -> StateQueueIter<'a, T, TContext> {
+        let (position, end) = self.bounds(ctx).page(page);
+        StateQueueIter { queue : self, ctx, position, end }
+    }
+
+    /// Sets the page of items requested through params PARAM_OFFSET and PARAM_LIMIT as results, from the front. Meant for views.
+    pub fn set_page_results<TContext : StateReader + Getter>(&self, ctx : &TContext) {
+        let page = Page::from_params(ctx);
+        collections::set_page_results(self.page_iter(page, ctx), self.len(ctx), ctx);
+    }
+}
+
+/// Positions of the first item and after the last item of a queue.
+/// Pushing to the front moves the head below 0, so positions may be negative.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bounds {
+    head : i64,
+    tail : i64,
+}
+
+impl Bounds {
+    fn len(&self) -> i32 {
+        (self.tail - self.head) as i32
+    }
+
+    fn is_full(&self) -> bool {
+        self.len() == i32::MAX
+    }
+
+    /// Returns the position of the first item. Returns None if the queue is empty.
+    fn front(&self) -> Option<i64> {
+        match self.head == self.tail {
+            true => None,
+            false => Some(self.head),
+        }
+    }
+
+    /// Returns the position of the last item. Returns None if the queue is empty.
+    fn back(&self) -> Option<i64> {
+        self.front().map(|_| self.tail - 1)
+    }
+
+    /// Returns the position of an item pushed to the back and the resulting bounds.
+    fn push_back(self) -> (i64, Bounds) {
+        (self.tail, Bounds { tail : self.tail + 1, ..self })
+    }
+
+    /// Returns the position of an item pushed to the front and the resulting bounds.
+    fn push_front(self) -> (i64, Bounds) {
+        (self.head - 1, Bounds { head : self.head - 1, ..self })
+    }
+
+    /// Returns the position of the item popped from the front and the resulting bounds. Returns None if the queue is empty.
+    fn pop_front(self) -> Option<(i64, Bounds)> {
+        let position = self.front()?;
+        Some((position, Bounds { head : position + 1, ..self }))
+    }
+
+    /// Returns the position of the item popped from the back and the resulting bounds. Returns None if the queue is empty.
+    fn pop_back(self) -> Option<(i64, Bounds)> {
+        let position = self.back()?;
+        Some((position, Bounds { tail : position, ..self }))
+    }
+
+    /// Returns the positions of the items of 'page', counted from the front, as (first, end).
+    fn page(&self, page : Page) -> (i64, i64) {
+        let (first, end) = page.bounds(self.len());
+        (self.head + first as i64, self.head + end as i64)
+    }
+}
+
+/// Iterator over a bounded range of items of a StateQueue, from the front.
+pub struct StateQueueIter<'a, T : MapValue, TContext : StateReader> {
+    queue : &'a StateQueue<T>,
+    ctx : &'a TContext,
+    position : i64,
+    end : i64,
+}
+
+impl<'a, T : MapValue, TContext : StateReader> Iterator for StateQueueIter<'a, T, TContext> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.position >= self.end {
+            return None;
+        }
+        let item = self.queue.read(self.position, self.ctx);
+        self.position += 1;
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.position).max(0) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, VecDeque};
+
+    const EMPTY : Bounds = Bounds { head : 0, tail : 0 };
+
+    /// Queue kept in memory, with items by position like StateQueue keeps them.
+    struct Queue {
+        bounds : Bounds,
+        slots : HashMap<i64, i64>,
+    }
+
+    impl Queue {
+        fn push_back(&mut self, item : i64) {
+            let (position, bounds) = self.bounds.push_back();
+            self.slots.insert(position, item);
+            self.bounds = bounds;
+        }
+
+        fn push_front(&mut self, item : i64) {
+            let (position, bounds) = self.bounds.push_front();
+            self.slots.insert(position, item);
+            self.bounds = bounds;
+        }
+
+        fn pop_front(&mut self) -> Option<i64> {
+            let (position, bounds) = self.bounds.pop_front()?;
+            self.bounds = bounds;
+            self.slots.remove(&position)
+        }
+
+        fn pop_back(&mut self) -> Option<i64> {
+            let (position, bounds) = self.bounds.pop_back()?;
+            self.bounds = bounds;
+            self.slots.remove(&position)
+        }
+
+        fn items(&self) -> Vec<i64> {
+            (self.bounds.head..self.bounds.tail).map(|position| self.slots[&position]).collect()
+        }
+    }
+
+    #[test]
+    fn empty_queue_has_no_front_or_back() {
+        assert_eq!(EMPTY.len(), 0);
+        assert_eq!(EMPTY.front(), None);
+        assert_eq!(EMPTY.back(), None);
+        assert_eq!(EMPTY.pop_front(), None);
+        assert_eq!(EMPTY.pop_back(), None);
+    }
+
+    #[test]
+    fn pushing_to_the_front_goes_below_zero() {
+        let (position, bounds) = EMPTY.push_front();
+        assert_eq!(position, -1);
+        assert_eq!(bounds, Bounds { head : -1, tail : 0 });
+        assert_eq!(bounds.front(), Some(-1));
+        assert_eq!(bounds.back(), Some(-1));
+        let (position, bounds) = bounds.pop_back().unwrap();
+        assert_eq!(position, -1);
+        assert_eq!(bounds.len(), 0);
+        assert_eq!(bounds.front(), None);
+    }
+
+    #[test]
+    fn matches_a_deque_across_position_zero() {
+        let mut queue = Queue { bounds : EMPTY, slots : HashMap::new() };
+        let mut expected : VecDeque<i64> = VecDeque::new();
+        // Drifts the head and tail below 0 and back above it.
+        for item in 0..40 {
+            match item % 4 {
+                0 | 1 => { queue.push_front(item); expected.push_front(item); },
+                2 => assert_eq!(queue.pop_back(), expected.pop_back()),
+                _ => { queue.push_back(item); expected.push_back(item); },
+            }
+            assert_eq!(queue.items(), expected.iter().cloned().collect::<Vec<i64>>());
+        }
+        assert!(queue.bounds.head < 0);
+        while !expected.is_empty() {
+            assert_eq!(queue.pop_front(), expected.pop_front());
+            queue.push_back(100);
+            expected.push_back(100);
+            assert_eq!(queue.pop_front(), expected.pop_front());
+        }
+        assert!(queue.bounds.head > 0);
+        assert_eq!(queue.bounds.len(), 0);
+        assert_eq!(queue.slots.len(), 0);
+    }
+
+    #[test]
+    fn pages_are_counted_from_the_front() {
+        let bounds = Bounds { head : -3, tail : 4 };
+        assert_eq!(bounds.page(Page { offset : 0, limit : 5 }), (-3, 2));
+        assert_eq!(bounds.page(Page { offset : 5, limit : 5 }), (2, 4));
+        assert_eq!(bounds.page(Page { offset : 10, limit : 5 }), (4, 4));
+    }
+
+    #[test]
+    fn is_full_at_i32_max_items() {
+        assert!(!Bounds { head : -1, tail : i32::MAX as i64 - 2 }.is_full());
+        assert!(Bounds { head : -1, tail : i32::MAX as i64 - 1 }.is_full());
+    }
+}
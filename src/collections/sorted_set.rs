@@ -0,0 +1,502 @@
+use std::ops::Bound;
+use wasmlib::*;
+use crate::codec::{self, Decode, DecodeError, Decoder, Encode};
+use crate::collections::{Page, StateMap};
+use crate::state::StateReader;
+
+/// Name of the id of the root node, inside the nested map of the set.
+const KEY_ROOT : &str = "root";
+/// Name of the id of the next node to be created, inside the nested map of the set.
+const KEY_NEXT_ID : &str = "next_id";
+/// Id which means "no node".
+const NO_NODE : u64 = 0;
+
+/// Node of the treap which keeps the items of a StateSortedSet.
+struct Node<T> {
+    item : T,
+    priority : u32,
+    left : u64,
+    right : u64,
+    size : u64,
+}
+
+impl<T : Encode> Encode for Node<T> {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        self.item.encode_to(output);
+        self.priority.encode_to(output);
+        self.left.encode_to(output);
+        self.right.encode_to(output);
+        self.size.encode_to(output);
+    }
+}
+
+impl<T : Decode> Decode for Node<T> {
+    fn decode_from(decoder : &mut Decoder) -> Result<Self, DecodeError> {
+        Ok(Node {
+            item : T::decode_from(decoder)?,
+            priority : u32::decode_from(decoder)?,
+            left : u64::decode_from(decoder)?,
+            right : u64::decode_from(decoder)?,
+            size : u64::decode_from(decoder)?,
+        })
+    }
+}
+
+/// Priority of the node 'id' in the treap, hashed (FNV-1a) from its id and 'seed', the id of the request which inserts it.
+/// Callers choose the items but neither of these, so they can't pick items which unbalance the tree.
+fn priority_of(id : u64, seed : &[u8]) -> u32 {
+    seed.iter().chain(id.to_le_bytes().iter()).fold(0x811c_9dc5_u32, |hash, byte| (hash ^ *byte as u32).wrapping_mul(0x0100_0193))
+}
+
+/// Storage of the nodes of a treap, so its algorithms run the same over the contract state and in memory.
+trait Nodes<T : Ord> {
+    /// Returns the node 'id'.
+    fn node(&self, id : u64) -> Node<T>;
+
+    /// Writes the node 'id'.
+    fn write_node(&mut self, id : u64, node : Node<T>);
+
+    /// Deletes the node 'id'.
+    fn delete_node(&mut self, id : u64);
+
+    /// Returns the amount of items of the tree 'id'.
+    fn size(&self, id : u64) -> u64 {
+        match id {
+            NO_NODE => 0,
+            _ => self.node(id).size,
+        }
+    }
+
+    /// Writes 'node' after recomputing its size from its children.
+    fn update_node(&mut self, id : u64, mut node : Node<T>) {
+        node.size = 1 + self.size(node.left) + self.size(node.right);
+        self.write_node(id, node);
+    }
+
+    /// Splits the tree 'id' into the trees of items lower than 'item' and the rest.
+    /// If 'equal_goes_left', items equal to 'item' go to the first tree instead.
+    fn split(&mut self, id : u64, item : &T, equal_goes_left : bool) -> (u64, u64) {
+        if id == NO_NODE {
+            return (NO_NODE, NO_NODE);
+        }
+        let mut node = self.node(id);
+        let goes_left = node.item < *item || (equal_goes_left && node.item == *item);
+        if goes_left {
+            let (left, right) = self.split(node.right, item, equal_goes_left);
+            node.right = left;
+            self.update_node(id, node);
+            (id, right)
+        } else {
+            let (left, right) = self.split(node.left, item, equal_goes_left);
+            node.left = right;
+            self.update_node(id, node);
+            (left, id)
+        }
+    }
+
+    /// Merges the trees 'left' and 'right', where every item of 'left' is lower than every item of 'right'.
+    fn merge(&mut self, left : u64, right : u64) -> u64 {
+        if left == NO_NODE {
+            return right;
+        }
+        if right == NO_NODE {
+            return left;
+        }
+        let mut left_node = self.node(left);
+        let mut right_node = self.node(right);
+        if left_node.priority > right_node.priority {
+            left_node.right = self.merge(left_node.right, right);
+            self.update_node(left, left_node);
+            left
+        } else {
+            right_node.left = self.merge(left, right_node.left);
+            self.update_node(right, right_node);
+            right
+        }
+    }
+
+    /// Adds the new node 'id', with 'item' and 'priority', to the tree 'root', which doesn't hold 'item'. Returns the new root.
+    fn insert(&mut self, root : u64, id : u64, item : T, priority : u32) -> u64 {
+        let (lower, higher) = self.split(root, &item, false);
+        self.write_node(id, Node { item, priority, left : NO_NODE, right : NO_NODE, size : 1 });
+        let lower_and_item = self.merge(lower, id);
+        self.merge(lower_and_item, higher)
+    }
+
+    /// Removes the node of 'item' from the tree 'root', which holds 'item'. Returns the new root.
+    fn remove(&mut self, root : u64, item : &T) -> u64 {
+        let (lower, rest) = self.split(root, item, false);
+        let (equal, higher) = self.split(rest, item, true);
+        self.delete_node(equal);
+        self.merge(lower, higher)
+    }
+}
+
+/// Nodes of a StateSortedSet, kept in a StateMap.
+struct StateNodes<'a, T : Encode + Decode + Ord> {
+    nodes : &'a StateMap<u64, Vec<u8>>,
+    ctx : &'a ScFuncContext,
+    item_type : std::marker::PhantomData<T>,
+}
+
+impl<'a, T : Encode + Decode + Ord> Nodes<T> for StateNodes<'a, T> {
+    fn node(&self, id : u64) -> Node<T> {
+        codec::must_decode(self.nodes.name(), &self.nodes.must_get(&id, self.ctx), self.ctx)
+    }
+
+    fn write_node(&mut self, id : u64, node : Node<T>) {
+        self.nodes.insert(&id, &node.encode(), self.ctx);
+    }
+
+    fn delete_node(&mut self, id : u64) {
+        self.nodes.remove(&id, self.ctx);
+    }
+}
+
+/// Sorted set persisted in the contract state, under the nested map 'name'. Items are stored with the crate's codec.
+/// Items are kept in a treap (a binary search tree balanced by node priorities), so inserting, removing and finding
+/// an item read and write O(log n) nodes, i.e. O(log n) host calls. Nodes are stored under 'name.nodes'.
+/// # Sample:
+/// ```ignore
+/// let bids : StateSortedSet<(i64, ScAgentID)> = StateSortedSet::new("bids");
+/// bids.insert((100, ctx.caller()), ctx);
+/// let highest_bid : Option<(i64, ScAgentID)> = bids.last(ctx);
+/// for bid in bids.range(Bound::Included(&(50, ScAgentID::from_bytes(&[0; 37]))), Bound::Unbounded, 10, ctx) {
+///     // ...
+/// }
+/// ```
+pub struct StateSortedSet<T : Encode + Decode + Ord> {
+    name : String,
+    nodes : StateMap<u64, Vec<u8>>,
+    item_type : std::marker::PhantomData<T>,
+}
+
+impl<T : Encode + Decode + Ord> StateSortedSet<T> {
+    /// Creates a set stored under 'name' in the contract state.
+    pub fn new(name : &str) -> StateSortedSet<T> {
+        StateSortedSet { name : name.to_string(), nodes : StateMap::new(&format!("{}.nodes", name)), item_type : std::marker::PhantomData }
+    }
+
+    /// Returns the name of the set in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn root<TContext : StateReader>(&self, ctx : &TContext) -> u64 {
+        ctx.immutable_state().get_map(self.name.as_str()).get_int64(KEY_ROOT).value() as u64
+    }
+
+    fn set_root(&self, id : u64, ctx : &ScFuncContext) {
+        ctx.state().get_map(self.name.as_str()).get_int64(KEY_ROOT).set_value(id as i64);
+    }
+
+    fn new_id(&self, ctx : &ScFuncContext) -> u64 {
+        let next_id = ctx.state().get_map(self.name.as_str()).get_int64(KEY_NEXT_ID);
+        // Ids start at 1, since 0 means "no node".
+        let id = next_id.value().max(1);
+        next_id.set_value(id + 1);
+        id as u64
+    }
+
+    fn node<TContext : StateReader>(&self, id : u64, ctx : &TContext) -> Node<T> {
+        codec::must_decode(self.nodes.name(), &self.nodes.must_get(&id, ctx), ctx)
+    }
+
+    fn size<TContext : StateReader>(&self, id : u64, ctx : &TContext) -> u64 {
+        match id {
+            NO_NODE => 0,
+            _ => self.node(id, ctx).size,
+        }
+    }
+
+    /// Returns the amount of items.
+    pub fn len<TContext : StateReader>(&self, ctx : &TContext) -> i32 {
+        self.size(self.root(ctx), ctx) as i32
+    }
+
+    /// Checks if there are no items. Returns true if it is empty.
+    pub fn is_empty<TContext : StateReader>(&self, ctx : &TContext) -> bool {
+        self.root(ctx) == NO_NODE
+    }
+
+    /// Checks if 'item' is in the set. Returns true if it is.
+    pub fn contains<TContext : StateReader>(&self, item : &T, ctx : &TContext) -> bool {
+        let mut id = self.root(ctx);
+        while id != NO_NODE {
+            let node = self.node(id, ctx);
+            if *item == node.item {
+                return true;
+            }
+            id = if *item < node.item { node.left } else { node.right };
+        }
+        false
+    }
+
+    /// Adds 'item' to the set. Returns false if it was already in it.
+    pub fn insert(&self, item : T, ctx : &ScFuncContext) -> bool {
+        if self.contains(&item, ctx) {
+            return false;
+        }
+        ctx.require(self.len(ctx) < i32::MAX, &format!("sorted set {} is full", self.name));
+        let id = self.new_id(ctx);
+        let priority = priority_of(id, ctx.request_id().to_bytes());
+        let root = StateNodes { nodes : &self.nodes, ctx, item_type : std::marker::PhantomData }.insert(self.root(ctx), id, item, priority);
+        self.set_root(root, ctx);
+        true
+    }
+
+    /// Removes 'item' from the set. Returns false if it was not in it.
+    pub fn remove(&self, item : &T, ctx : &ScFuncContext) -> bool {
+        if !self.contains(item, ctx) {
+            return false;
+        }
+        let root = StateNodes { nodes : &self.nodes, ctx, item_type : std::marker::PhantomData }.remove(self.root(ctx), item);
+        self.set_root(root, ctx);
+        true
+    }
+
+    /// Returns the lowest item. Returns None if it is empty.
+    pub fn first<TContext : StateReader>(&self, ctx : &TContext) -> Option<T> {
+        self.iter(0, 1, ctx).next()
+    }
+
+    /// Returns the highest item. Returns None if it is empty.
+    pub fn last<TContext : StateReader>(&self, ctx : &TContext) -> Option<T> {
+        let mut id = self.root(ctx);
+        let mut last = None;
+        while id != NO_NODE {
+            let node = self.node(id, ctx);
+            id = node.right;
+            last = Some(node.item);
+        }
+        last
+    }
+
+    /// Removes the lowest item and returns it. Returns None if it is empty.
+    pub fn pop_first(&self, ctx : &ScFuncContext) -> Option<T> {
+        let item = self.first(ctx)?;
+        self.remove(&item, ctx);
+        Some(item)
+    }
+
+    /// Removes the highest item and returns it. Returns None if it is empty.
+    pub fn pop_last(&self, ctx : &ScFuncContext) -> Option<T> {
+        let item = self.last(ctx)?;
+        self.remove(&item, ctx);
+        Some(item)
+    }
+
+    /// Iterates in order over at most 'limit' items, starting at the item in position 'offset'.
+    pub fn iter<'a, TContext : StateReader>(&'a self, offset : i32, limit : i32, ctx : &'a TContext) -> StateSortedSetIter<'a, T, TContext> {
+        let page = Page::iteration(offset, limit, ctx);
+        let mut iter = StateSortedSetIter { set : self, ctx, stack : Vec::new(), end : Bound::Unbounded, remaining : page.limit };
+
+        // Descends to the item in position 'offset', keeping the nodes still to be visited in the stack.
+        let mut skip = page.offset as u64;
+        let mut id = self.root(ctx);
+        while id != NO_NODE {
+            let node = self.node(id, ctx);
+            let left_size = self.size(node.left, ctx);
+            if skip < left_size {
+                id = node.left;
+                iter.stack.push(node);
+            } else if skip == left_size {
+                iter.stack.push(node);
+                break;
+            } else {
+                skip -= left_size + 1;
+                id = node.right;
+            }
+        }
+        iter
+    }
+
+    /// Iterates in order over at most 'limit' items between 'start' and 'end'.
+    pub fn range<'a, TContext : StateReader>(&'a self, start : Bound<&T>, end : Bound<&'a T>, limit : i32, ctx : &'a TContext) -> StateSortedSetIter<'a, T, TContext> {
+        let page = Page::iteration(0, limit, ctx);
+        let mut iter = StateSortedSetIter { set : self, ctx, stack : Vec::new(), end, remaining : page.limit };
+
+        // Descends to the first item after 'start', keeping the nodes still to be visited in the stack.
+        let mut id = self.root(ctx);
+        while id != NO_NODE {
+            let node = self.node(id, ctx);
+            let after_start = match start {
+                Bound::Included(start) => node.item >= *start,
+                Bound::Excluded(start) => node.item > *start,
+                Bound::Unbounded => true,
+            };
+            if after_start {
+                id = node.left;
+                iter.stack.push(node);
+            } else {
+                id = node.right;
+            }
+        }
+        iter
+    }
+}
+
+/// In-order iterator over a bounded range of items of a StateSortedSet.
+pub struct StateSortedSetIter<'a, T : Encode + Decode + Ord, TContext : StateReader> {
+    set : &'a StateSortedSet<T>,
+    ctx : &'a TContext,
+    stack : Vec<Node<T>>,
+    end : Bound<&'a T>,
+    remaining : i32,
+}
+
+impl<'a, T : Encode + Decode + Ord, TContext : StateReader> Iterator for StateSortedSetIter<'a, T, TContext> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining <= 0 {
+            return None;
+        }
+        let node = self.stack.pop()?;
+        let before_end = match self.end {
+            Bound::Included(end) => node.item <= *end,
+            Bound::Excluded(end) => node.item < *end,
+            Bound::Unbounded => true,
+        };
+        if !before_end {
+            self.stack.clear();
+            return None;
+        }
+
+        // Next items are the right subtree, starting at its lowest item.
+        let mut id = node.right;
+        while id != NO_NODE {
+            let child = self.set.node(id, self.ctx);
+            id = child.left;
+            self.stack.push(child);
+        }
+        self.remaining -= 1;
+        Some(node.item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Treap kept in memory, with the root and next id next to the nodes like StateSortedSet keeps them.
+    struct Treap {
+        nodes : BTreeMap<u64, Node<i64>>,
+        root : u64,
+        next_id : u64,
+    }
+
+    impl Nodes<i64> for BTreeMap<u64, Node<i64>> {
+        fn node(&self, id : u64) -> Node<i64> {
+            let node = &self[&id];
+            Node { item : node.item, priority : node.priority, left : node.left, right : node.right, size : node.size }
+        }
+
+        fn write_node(&mut self, id : u64, node : Node<i64>) {
+            BTreeMap::insert(self, id, node);
+        }
+
+        fn delete_node(&mut self, id : u64) {
+            BTreeMap::remove(self, &id);
+        }
+    }
+
+    impl Treap {
+        fn new() -> Treap {
+            Treap { nodes : BTreeMap::new(), root : NO_NODE, next_id : 1 }
+        }
+
+        fn insert(&mut self, item : i64, seed : &[u8]) {
+            let id = self.next_id;
+            self.next_id += 1;
+            self.root = Nodes::insert(&mut self.nodes, self.root, id, item, priority_of(id, seed));
+        }
+
+        fn remove(&mut self, item : i64) {
+            self.root = Nodes::remove(&mut self.nodes, self.root, &item);
+        }
+
+        /// Checks the order of items, the priorities and the sizes of the tree 'id'. Returns its items in order and its height.
+        fn check(&self, id : u64) -> (Vec<i64>, u32) {
+            if id == NO_NODE {
+                return (Vec::new(), 0);
+            }
+            let node = &self.nodes[&id];
+            for child in [node.left, node.right].iter().filter(|child| **child != NO_NODE) {
+                assert!(self.nodes[child].priority <= node.priority, "child {} has a higher priority than its parent {}", child, id);
+            }
+            let (mut items, left_height) = self.check(node.left);
+            if let Some(last) = items.last() {
+                assert!(*last < node.item, "items out of order at node {}", id);
+            }
+            items.push(node.item);
+            let (right_items, right_height) = self.check(node.right);
+            if let Some(first) = right_items.first() {
+                assert!(*first > node.item, "items out of order at node {}", id);
+            }
+            items.extend(right_items);
+            assert_eq!(node.size, items.len() as u64, "size of node {}", id);
+            (items, 1 + left_height.max(right_height))
+        }
+
+        fn items(&self) -> Vec<i64> {
+            let (items, _) = self.check(self.root);
+            assert_eq!(self.nodes.len(), items.len(), "nodes left behind");
+            items
+        }
+
+        fn height(&self) -> u32 {
+            self.check(self.root).1
+        }
+    }
+
+    #[test]
+    fn keeps_items_sorted_and_priorities_ordered() {
+        let mut treap = Treap::new();
+        let mut expected = Vec::new();
+        for index in 0..300_i64 {
+            let item = (index * 7_919) % 1_009;
+            treap.insert(item, &index.to_le_bytes());
+            expected.push(item);
+        }
+        expected.sort_unstable();
+        assert_eq!(treap.items(), expected);
+
+        for item in expected.clone().iter().step_by(3) {
+            treap.remove(*item);
+            expected.retain(|other| other != item);
+            assert_eq!(treap.items(), expected);
+        }
+        for item in expected.clone() {
+            treap.remove(item);
+        }
+        assert_eq!(treap.root, NO_NODE);
+        assert!(treap.items().is_empty());
+    }
+
+    #[test]
+    fn stays_balanced_for_sorted_items() {
+        // Inserting sorted items would make a plain binary search tree a list.
+        let mut treap = Treap::new();
+        for item in 0..1_000_i64 {
+            treap.insert(item, b"request");
+        }
+        assert_eq!(treap.items(), (0..1_000).collect::<Vec<i64>>());
+        assert!(treap.height() < 40, "height {} for 1000 items", treap.height());
+    }
+
+    #[test]
+    fn priorities_dont_depend_on_items() {
+        let mut low_items = Treap::new();
+        let mut high_items = Treap::new();
+        for item in 0..50_i64 {
+            low_items.insert(item, b"request");
+            high_items.insert(item + 1_000, b"request");
+        }
+        let priorities = |treap : &Treap| treap.nodes.values().map(|node| node.priority).collect::<Vec<u32>>();
+        assert_eq!(priorities(&low_items), priorities(&high_items));
+        assert_ne!(priority_of(1, b"request"), priority_of(2, b"request"));
+        assert_ne!(priority_of(1, b"request"), priority_of(1, b"another request"));
+    }
+}
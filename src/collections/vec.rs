@@ -0,0 +1,181 @@
+use std::marker::PhantomData;
+use wasmlib::*;
+use crate::collections::{self, Page};
+use crate::getter::Getter;
+use crate::state::StateReader;
+use crate::values::MapValue;
+
+/// Name of the length of the vec, inside its nested map.
+const KEY_LENGTH : &str = "length";
+/// Name of the array of items of the vec, inside its nested map.
+const KEY_ITEMS : &str = "items";
+
+/// Typed vector persisted in the contract state, under the nested map 'name'.
+/// Items are kept in a wasmlib array. Since wasmlib arrays can't shrink, the length of the vec is kept next to it.
+/// # Sample:
+/// ```ignore
+/// let holders : StateVec<ScAgentID> = StateVec::new("holders");
+/// holders.push(&ctx.caller(), ctx);
+/// let first_holder : ScAgentID = holders.must_get(0, ctx);
+/// ```
+pub struct StateVec<T : MapValue> {
+    name : String,
+    _type : PhantomData<T>,
+}
+
+impl<T : MapValue> StateVec<T> {
+    /// Creates a vec stored under 'name' in the contract state.
+    pub fn new(name : &str) -> StateVec<T> {
+        StateVec { name : name.to_string(), _type : PhantomData }
+    }
+
+    /// Returns the name of the vec in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn immutable_map<TContext : StateReader>(&self, ctx : &TContext) -> ScImmutableMap {
+        ctx.immutable_state().get_map(self.name.as_str())
+    }
+
+    fn mutable_map(&self, ctx : &ScFuncContext) -> ScMutableMap {
+        ctx.state().get_map(self.name.as_str())
+    }
+
+    fn set_len(&self, length : i32, ctx : &ScFuncContext) {
+        self.mutable_map(ctx).get_int64(KEY_LENGTH).set_value(length as i64);
+    }
+
+    fn slot(&self, index : i32, ctx : &ScFuncContext) -> ScMutableMap {
+        self.mutable_map(ctx).get_map_array(KEY_ITEMS).get_map(index)
+    }
+
+    fn require_in_bounds<TContext : ScBaseContext>(&self, index : i32, length : i32, ctx : &TContext) {
+        ctx.require(index >= 0 && index < length, &format!("index {} out of bounds of vec {} with length {}", index, self.name, length));
+    }
+
+    /// Returns the amount of items.
+    pub fn len<TContext : StateReader>(&self, ctx : &TContext) -> i32 {
+        self.immutable_map(ctx).get_int64(KEY_LENGTH).value() as i32
+    }
+
+    /// Checks if there are no items. Returns true if it is empty.
+    pub fn is_empty<TContext : StateReader>(&self, ctx : &TContext) -> bool {
+        self.len(ctx) == 0
+    }
+
+    /// Tries to get the item at 'index'. Returns None if it is out of bounds.
+    pub fn get<TContext : StateReader>(&self, index : i32, ctx : &TContext) -> Option<T> {
+        if index < 0 || index >= self.len(ctx) {
+            return None;
+        }
+        collections::read_slot(&self.immutable_map(ctx).get_map_array(KEY_ITEMS).get_map(index), ctx)
+    }
+
+    /// Tries to get the item at 'index'. Panics if it is out of bounds.
+    pub fn must_get<TContext : StateReader>(&self, index : i32, ctx : &TContext) -> T {
+        self.require_in_bounds(index, self.len(ctx), ctx);
+        match self.get(index, ctx) {
+            Some(item) => item,
+            None => {
+                let message = format!("item {} not found in vec {}", index, self.name);
+                ctx.panic(&message);
+                panic!("{}", message)
+            }
+        }
+    }
+
+    /// Replaces the item at 'index'. Panics if it is out of bounds.
+    pub fn set(&self, index : i32, item : &T, ctx : &ScFuncContext) {
+        self.require_in_bounds(index, self.len(ctx), ctx);
+        collections::write_slot(&self.slot(index, ctx), item);
+    }
+
+    /// Appends 'item' to the end.
+    pub fn push(&self, item : &T, ctx : &ScFuncContext) {
+        let length = self.len(ctx);
+        ctx.require(length < i32::MAX, &format!("vec {} is full", self.name));
+        collections::write_slot(&self.slot(length, ctx), item);
+        self.set_len(length + 1, ctx);
+    }
+
+    /// Removes the last item and returns it. Returns None if it is empty.
+    pub fn pop(&self, ctx : &ScFuncContext) -> Option<T> {
+        let length = self.len(ctx);
+        if length == 0 {
+            return None;
+        }
+        let last_index = length - 1;
+        let item = self.get(last_index, ctx);
+        collections::delete_slot(&self.slot(last_index, ctx));
+        self.set_len(last_index, ctx);
+        item
+    }
+
+    /// Removes the item at 'index' and returns it. The last item takes its place, so the order of items is not kept.
+    /// Panics if 'index' is out of bounds.
+    pub fn swap_remove(&self, index : i32, ctx : &ScFuncContext) -> T {
+        let item = self.must_get(index, ctx);
+        let last_index = self.len(ctx) - 1;
+        if index != last_index {
+            let last_item = self.must_get(last_index, ctx);
+            collections::write_slot(&self.slot(index, ctx), &last_item);
+        }
+        collections::delete_slot(&self.slot(last_index, ctx));
+        self.set_len(last_index, ctx);
+        item
+    }
+
+    /// Removes every item.
+    pub fn clear(&self, ctx : &ScFuncContext) {
+        self.mutable_map(ctx).get_map_array(KEY_ITEMS).clear();
+        self.set_len(0, ctx);
+    }
+
+    /// Iterates over at most 'limit' items, starting at 'offset'.
+    pub fn iter<'a, TContext : StateReader>(&'a self, offset : i32, limit : i32, ctx : &'a TContext) -> StateVecIter<'a, T, TContext> {
+        let page = Page::iteration(offset, limit, ctx);
+        let (index, end) = page.bounds(self.len(ctx));
+        StateVecIter { vec : self, ctx, index, end }
+    }
+
+    /// Sets the page of items requested through params PARAM_OFFSET and PARAM_LIMIT as results. Meant for views.
+    /// # Sample:
+    /// ```ignore
+    /// fn view_get_holders(ctx : &ScViewContext) {
+    ///     StateVec::<ScAgentID>::new("holders").set_page_results(ctx);
+    /// }
+    /// ```
+    pub fn set_page_results<TContext : StateReader + Getter>(&self, ctx : &TContext) {
+        let page = Page::from_params(ctx);
+        let total = self.len(ctx);
+        let (index, end) = page.bounds(total);
+        collections::set_page_results(StateVecIter { vec : self, ctx, index, end }, total, ctx);
+    }
+}
+
+/// Iterator over a bounded range of items of a StateVec.
+pub struct StateVecIter<'a, T : MapValue, TContext : StateReader> {
+    vec : &'a StateVec<T>,
+    ctx : &'a TContext,
+    index : i32,
+    end : i32,
+}
+
+impl<'a, T : MapValue, TContext : StateReader> Iterator for StateVecIter<'a, T, TContext> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.index >= self.end {
+            return None;
+        }
+        let item = self.vec.must_get(self.index, self.ctx);
+        self.index += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.index).max(0) as usize;
+        (remaining, Some(remaining))
+    }
+}
@@ -0,0 +1 @@
+pub use iota_sc_utils_derive::*;
\ No newline at end of file
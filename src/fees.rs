@@ -0,0 +1,230 @@
+use wasmlib::*;
+use crate::codec::{Decode, DecodeError, Decoder, Encode, Encoded};
+use crate::collections::{IterableStateMap, Page, MAX_PAGE_LIMIT};
+use crate::getter::Getter;
+use crate::incoming;
+use crate::math::{self, SafeMath, BASIS_POINTS_TOTAL};
+use crate::outgoing;
+use crate::results;
+use crate::state::StateReader;
+use crate::tokens::{ColorKey, Tokens};
+use crate::values::MapValue;
+
+/// Name of the result array with one map per function, written by `set_schedule_results`.
+pub const RESULT_FEES : &str = "fees";
+/// Name of the hname of the function, in each map of RESULT_FEES.
+pub const RESULT_FUNCTION : &str = "function";
+/// Name of the percentage fee in basis points, in each map of RESULT_FEES.
+pub const RESULT_BASIS_POINTS : &str = "basis_points";
+/// Name of the array of colors of fixed fees, in each map of RESULT_FEES.
+pub const RESULT_FIXED_COLORS : &str = "fixed_colors";
+/// Name of the array of amounts of fixed fees, in each map of RESULT_FEES. Same order as RESULT_FIXED_COLORS.
+pub const RESULT_FIXED_AMOUNTS : &str = "fixed_amounts";
+
+/// Fee of a function: fixed amounts of some colors plus a percentage, in basis points, of every incoming color.
+#[derive(Default)]
+pub struct Fee {
+    /// Fixed amounts charged, by color.
+    pub fixed : Vec<(ScColor, i64)>,
+    /// Percentage of every incoming color charged, in basis points.
+    pub basis_points : i64,
+}
+
+impl Fee {
+    /// Creates a fee of 'amount' tokens of 'color'.
+    pub fn fixed(color : &ScColor, amount : i64) -> Fee {
+        Fee { fixed : Vec::new(), basis_points : 0 }.with_fixed(color, amount)
+    }
+
+    /// Creates a fee of 'basis_points' of every incoming color.
+    pub fn basis_points(basis_points : i64) -> Fee {
+        Fee { fixed : Vec::new(), basis_points }
+    }
+
+    /// Adds a fixed fee of 'amount' tokens of 'color'.
+    pub fn with_fixed(mut self, color : &ScColor, amount : i64) -> Fee {
+        self.fixed.push((ScColor::from_bytes(color.to_bytes()), amount));
+        self
+    }
+
+    /// Returns the amount due in 'color' when 'incoming_amount' tokens of it are attached.
+    pub fn amount_due<TContext : ScBaseContext>(&self, color : &ScColor, incoming_amount : i64, ctx : &TContext) -> i64 {
+        let fixed = self.fixed.iter().filter(|(fixed_color, _)| fixed_color == color).fold(0_i64, |total, (_, amount)| total.safe_add(amount, ctx));
+        fixed.safe_add(&math::basis_points_of(incoming_amount, self.basis_points, ctx), ctx)
+    }
+}
+
+impl Encode for Fee {
+    fn encode_to(&self, output : &mut Vec<u8>) {
+        self.fixed.encode_to(output);
+        self.basis_points.encode_to(output);
+    }
+}
+
+impl Decode for Fee {
+    fn decode_from(decoder : &mut Decoder) -> Result<Fee, DecodeError> {
+        Ok(Fee { fixed : Vec::decode_from(decoder)?, basis_points : i64::decode_from(decoder)? })
+    }
+}
+
+/**
+Collects fees of functions from their incoming tokens into a treasury kept in the contract state.
+Fees are set per function hname. Only the admin, by default the contract creator, may change fees, withdraw from the treasury or hand over the role.
+Stored under 'name.schedule', 'name.treasury' and 'name.admin'.
+# Sample:
+```ignore
+let fees = Fees::new("fees");
+fees.set_fee(HNAME_FUNC_MINT, Fee::fixed(&ScColor::IOTA, 10).with_fixed(&my_color, 1), ctx);
+fees.set_fee(HNAME_FUNC_TRADE, Fee::basis_points(30), ctx);
+
+// In "trade". Panics if the incoming tokens don't cover the fee.
+let charged : Tokens = fees.charge(HNAME_FUNC_TRADE, ctx);
+
+// Fee-schedule view
+fees.set_schedule_results(ctx);
+```
+*/
+pub struct Fees {
+    name : String,
+    schedule : IterableStateMap<ScHname, Encoded<Fee>>,
+    treasury : IterableStateMap<ScColor, i64>,
+}
+
+impl Fees {
+    /// Creates fees stored under 'name' in the contract state.
+    pub fn new(name : &str) -> Fees {
+        Fees {
+            name : name.to_string(),
+            schedule : IterableStateMap::new(&format!("{}.schedule", name)),
+            treasury : IterableStateMap::new(&format!("{}.treasury", name)),
+        }
+    }
+
+    /// Returns the name of the fees in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn admin_name(&self) -> String {
+        format!("{}.admin", self.name)
+    }
+
+    /// Returns the agent which may change fees and withdraw from the treasury. Defaults to the contract creator.
+    pub fn admin<TContext : StateReader>(&self, ctx : &TContext) -> ScAgentID {
+        let admin_name = self.admin_name();
+        let state = ctx.immutable_state();
+        match ScAgentID::exists_in(&admin_name, &state) {
+            true => ScAgentID::get_from(&admin_name, &state),
+            false => ctx.contract_creator(),
+        }
+    }
+
+    /// Panics if the caller is not the admin.
+    pub fn caller_must_be_admin(&self, ctx : &ScFuncContext) {
+        ctx.require(ctx.caller() == self.admin(ctx), &format!("Only the admin of {} may call this function!", self.name));
+    }
+
+    /// Hands the admin role over to 'admin_id'. Only the admin may call it.
+    pub fn set_admin(&self, admin_id : &ScAgentID, ctx : &ScFuncContext) {
+        self.caller_must_be_admin(ctx);
+        admin_id.set_in(&self.admin_name(), &ctx.state());
+        ctx.event(&format!("fees.admin_changed {} {}", self.name, admin_id.to_string()));
+    }
+
+    /// Sets the fee of the function 'hfunction'. Only the admin may call it.
+    /// Panics if a fixed amount is not positive or the basis points are not between 0 and BASIS_POINTS_TOTAL.
+    pub fn set_fee(&self, hfunction : ScHname, fee : Fee, ctx : &ScFuncContext) {
+        self.caller_must_be_admin(ctx);
+        ctx.require(fee.basis_points >= 0 && fee.basis_points <= BASIS_POINTS_TOTAL, &format!("Invalid fee of {} basis points. It must be between 0 and {}.", fee.basis_points, BASIS_POINTS_TOTAL));
+        for (color, amount) in fee.fixed.iter() {
+            ctx.require(*amount > 0, &format!("Invalid fixed fee {} of color {}. It must be positive.", amount, color.to_string()));
+        }
+        ctx.require(self.schedule.contains_key(&hfunction, ctx) || self.schedule.len(ctx) < MAX_PAGE_LIMIT, &format!("Too many functions with fees. Maximum: {}.", MAX_PAGE_LIMIT));
+        ctx.event(&format!("fees.changed {} {} {}", self.name, hfunction.to_string(), crate::codec::to_hex(&fee.encode())));
+        self.schedule.insert(&hfunction, &Encoded(fee), ctx);
+    }
+
+    /// Removes the fee of the function 'hfunction', so it is free. Only the admin may call it.
+    pub fn remove_fee(&self, hfunction : ScHname, ctx : &ScFuncContext) {
+        self.caller_must_be_admin(ctx);
+        if self.schedule.remove(&hfunction, ctx).is_some() {
+            ctx.event(&format!("fees.removed {} {}", self.name, hfunction.to_string()));
+        }
+    }
+
+    /// Returns the fee of the function 'hfunction', or None if it is free.
+    pub fn fee_of<TContext : StateReader>(&self, hfunction : ScHname, ctx : &TContext) -> Option<Fee> {
+        self.schedule.get(&hfunction, ctx).map(|fee| fee.0)
+    }
+
+    /// Charges the fee of the function 'hfunction' from the incoming tokens and adds it to the treasury.
+    /// Panics if the incoming tokens don't cover it. Returns the charged tokens, by color. The rest of the incoming tokens is left to the function.
+    pub fn charge(&self, hfunction : ScHname, ctx : &ScFuncContext) -> Tokens {
+        let mut charged = Tokens::new();
+        let fee = match self.fee_of(hfunction, ctx) {
+            Some(fee) => fee,
+            None => return charged,
+        };
+        let mut colors = incoming::all(ctx);
+        for (color, _) in fee.fixed.iter() {
+            colors.entry(ColorKey::from(color)).or_insert(0);
+        }
+        for (color, incoming_amount) in colors.iter() {
+            let amount_due = fee.amount_due(color, *incoming_amount, ctx);
+            if amount_due == 0 {
+                continue;
+            }
+            incoming::require_balance(amount_due, color, ctx);
+            let collected = self.collected(color, ctx).safe_add(&amount_due, ctx);
+            self.treasury.insert(color, &collected, ctx);
+            ctx.event(&format!("fees.charged {} {} {} {}", self.name, hfunction.to_string(), color.to_string(), amount_due));
+            charged.insert(color.clone(), amount_due);
+        }
+        charged
+    }
+
+    /// Returns the amount of tokens of 'color' in the treasury.
+    pub fn collected<TContext : StateReader>(&self, color : &ScColor, ctx : &TContext) -> i64 {
+        self.treasury.get(color, ctx).unwrap_or(0)
+    }
+
+    /// Returns the amounts of tokens in the treasury of the colors in 'page', by color. Colors are kept in the order they were first charged.
+    pub fn collected_page<TContext : StateReader>(&self, page : Page, ctx : &TContext) -> Tokens {
+        self.treasury.iter(page.offset, page.limit, ctx).map(|(color, amount)| (ColorKey(color), amount)).collect()
+    }
+
+    /// Sets the page of the treasury requested through params PARAM_OFFSET and PARAM_LIMIT as results, for a view.
+    /// Colors are set in RESULT_KEYS and their amounts in RESULT_ITEMS, at the same positions.
+    pub fn set_collected_results<TContext : StateReader + Getter>(&self, ctx : &TContext) {
+        self.treasury.set_page_results(ctx);
+    }
+
+    /// Sends 'amount' tokens of 'color' of the treasury to 'agent_id'. Only the admin may call it.
+    /// Panics if the treasury holds less than 'amount'.
+    pub fn withdraw(&self, color : &ScColor, amount : i64, agent_id : &ScAgentID, ctx : &ScFuncContext) {
+        self.caller_must_be_admin(ctx);
+        let collected = self.collected(color, ctx);
+        ctx.require(amount > 0 && amount <= collected, &format!("Invalid withdrawal of {} of color {}. Collected: {}.", amount, color.to_string(), collected));
+        self.treasury.insert(color, &collected.safe_sub(&amount, ctx), ctx);
+        outgoing::send_to_agent(agent_id, color, amount, ctx);
+        ctx.event(&format!("fees.withdrawn {} {} {} {}", self.name, agent_id.to_string(), color.to_string(), amount));
+    }
+
+    /// Sets the fee schedule as results, for a view: one map per function in the array RESULT_FEES.
+    pub fn set_schedule_results<TContext : StateReader>(&self, ctx : &TContext) {
+        let writer = results::writer(ctx);
+        let count = self.schedule.len(ctx);
+        if count == 0 {
+            return;
+        }
+        for (hfunction, fee) in self.schedule.iter(0, count, ctx) {
+            let fixed_colors : Vec<ScColor> = fee.0.fixed.iter().map(|(color, _)| ScColor::from_bytes(color.to_bytes())).collect();
+            let fixed_amounts : Vec<i64> = fee.0.fixed.iter().map(|(_, amount)| *amount).collect();
+            writer.push_map(RESULT_FEES)
+                .set(RESULT_FUNCTION, hfunction)
+                .set(RESULT_BASIS_POINTS, fee.0.basis_points)
+                .set_array(RESULT_FIXED_COLORS, &fixed_colors)
+                .set_array(RESULT_FIXED_AMOUNTS, &fixed_amounts);
+        }
+    }
+}
@@ -1,4 +1,7 @@
 use wasmlib::*;
+use crate::math::SafeMath;
+use crate::outgoing;
+use crate::tokens::{self, ColorKey, Tokens};
 
 /// Returns the amount of tokens of 'color' attached to the call.
 pub fn balance(color : &ScColor, ctx : &ScFuncContext) -> i64 {
@@ -16,4 +19,119 @@ pub fn colors(ctx : &ScFuncContext) -> ScImmutableColorArray {
 pub fn require_balance(minimum_balance : i64, color : &ScColor, ctx : &ScFuncContext) {
     let incoming_balance = balance(color, ctx);
     ctx.require(incoming_balance >= minimum_balance, &format!("Insuficient incoming balance of color {}. Minimum required: {}.", color.to_string(), minimum_balance));
-}
\ No newline at end of file
+}
+
+/// What to do with attached tokens of colors a policy doesn't allow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Disallowed {
+    /// Panics, so the tokens go back with the failed request.
+    Reject,
+    /// Sends the tokens back to the caller and goes on.
+    RefundToCaller,
+}
+
+/// Returns every amount of tokens attached to the call, by color. Colors without tokens are left out.
+pub fn all(ctx : &ScFuncContext) -> Tokens {
+    let incoming_colors = colors(ctx);
+    let mut incoming_tokens = Tokens::new();
+    for index in 0..incoming_colors.length() {
+        let color = incoming_colors.get_color(index).value();
+        let amount = balance(&color, ctx);
+        if amount > 0 {
+            incoming_tokens.insert(ColorKey(color), amount);
+        }
+    }
+    incoming_tokens
+}
+
+/// Returns the sum of the amounts of every color attached to the call. Panics on overflows.
+pub fn total_tokens(ctx : &ScFuncContext) -> i64 {
+    tokens::total(&all(ctx), ctx)
+}
+
+/// Checks if no token is attached to the call. Returns true if there is none.
+pub fn is_empty(ctx : &ScFuncContext) -> bool {
+    all(ctx).is_empty()
+}
+
+/// Panics if incoming balance of 'color' is not exactly 'amount'.
+pub fn require_exact(amount : i64, color : &ScColor, ctx : &ScFuncContext) {
+    let incoming_balance = balance(color, ctx);
+    ctx.require(incoming_balance == amount, &format!("Invalid incoming balance of color {}. Required: exactly {}, attached: {}.", color.to_string(), amount, incoming_balance));
+}
+
+/// Panics if any token is attached to the call. For functions which must not receive funds.
+pub fn require_no_incoming(ctx : &ScFuncContext) {
+    if let Some((color, amount)) = all(ctx).iter().next() {
+        let message = format!("No incoming tokens allowed. Attached: {} of color {}.", amount, color.to_string());
+        ctx.panic(&message);
+        panic!("{}", message)
+    }
+}
+
+/// Checks that only tokens of the 'allowed' colors are attached to the call. Tokens of other colors are rejected or refunded to the caller.
+pub fn require_only_colors(allowed : &[ScColor], disallowed : Disallowed, ctx : &ScFuncContext) {
+    let mut refund = Tokens::new();
+    for (color, amount) in all(ctx).into_iter().filter(|(color, _)| !allowed.contains(color.color())) {
+        if disallowed == Disallowed::Reject {
+            let message = format!("Incoming tokens of color {} are not allowed. Attached: {}.", color.to_string(), amount);
+            ctx.panic(&message);
+            panic!("{}", message)
+        }
+        refund.insert(color, amount);
+    }
+    if !refund.is_empty() {
+        outgoing::send_multi(&ctx.caller(), &refund, ctx);
+    }
+}
+
+/// Checks that tokens of at least one of the 'allowed' colors are attached to the call, and only of those colors.
+/// Tokens of other colors are rejected or refunded to the caller. Returns the first allowed color attached.
+pub fn require_any_of(allowed : &[ScColor], disallowed : Disallowed, ctx : &ScFuncContext) -> ScColor {
+    let attached_color = all(ctx).into_keys().map(|color| color.0).find(|color| allowed.contains(color));
+    let color = match attached_color {
+        Some(color) => color,
+        None => {
+            let allowed_names : Vec<String> = allowed.iter().map(|color| color.to_string()).collect();
+            let message = format!("Incoming tokens of one of the colors {} required.", allowed_names.join(", "));
+            ctx.panic(&message);
+            panic!("{}", message)
+        }
+    };
+    require_only_colors(allowed, disallowed, ctx);
+    color
+}
+
+/// Name of the result with the amount kept by `charge`.
+pub const RESULT_CHARGED : &str = "charged";
+/// Name of the result with the color charged by `charge`.
+pub const RESULT_CHARGED_COLOR : &str = "charged_color";
+/// Name of the result with the amount refunded by `charge`.
+pub const RESULT_REFUNDED : &str = "refunded";
+
+/// Charges 'amount' tokens of 'color' from the incoming ones and refunds the remainder to the caller.
+/// Panics if less than 'amount' is attached. Returns the refunded amount.
+pub fn charge(amount : i64, color : &ScColor, ctx : &ScFuncContext) -> i64 {
+    charge_with_minimum_refund(amount, color, 1, ctx)
+}
+
+/// Charges 'amount' tokens of 'color' from the incoming ones and refunds the remainder to the caller if it is at least 'minimum_refund'.
+/// Smaller remainders (dust) are kept by the contract and charged too. Panics if less than 'amount' is attached.
+/// Records the charged amount, color and refunded amount in the results. Returns the refunded amount.
+pub fn charge_with_minimum_refund(amount : i64, color : &ScColor, minimum_refund : i64, ctx : &ScFuncContext) -> i64 {
+    ctx.require(amount >= 0, &format!("Invalid charge {} of color {}. It must not be negative.", amount, color.to_string()));
+    require_balance(amount, color, ctx);
+    let remainder = balance(color, ctx).safe_sub(&amount, ctx);
+    let refunded = match remainder > 0 && remainder >= minimum_refund {
+        true => remainder,
+        false => 0,
+    };
+    if refunded > 0 {
+        outgoing::send_to_agent(&ctx.caller(), color, refunded, ctx);
+    }
+    let results = ctx.results();
+    results.get_int64(RESULT_CHARGED).set_value(amount.safe_add(&(remainder - refunded), ctx));
+    results.get_color(RESULT_CHARGED_COLOR).set_value(color);
+    results.get_int64(RESULT_REFUNDED).set_value(refunded);
+    refunded
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::ops::Deref;
+use wasmlib::*;
+use crate::collections::StateKey;
+
+// Keys are encoded as "<hname of namespace, 8 hex digits>.<key name>", followed by ".<length>:<part>" for every part.
+// Namespace and key names may only contain ASCII letters, digits and '_', and parts are length-prefixed,
+// so two different keys never have the same encoding.
+
+/// Checks if 'name' is a valid namespace or key name: not empty, only ASCII letters, digits and '_'.
+pub const fn is_valid_name(name : &str) -> bool {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() {
+        return false;
+    }
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if !(byte.is_ascii_alphanumeric() || byte == b'_') {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+const fn names_are_equal(a : &str, b : &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < a.len() {
+        if a[index] != b[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+/// Fails if any namespace is invalid or two namespaces have the same name or hname.
+/// Used by `namespaces!`, so the registry is checked at compile time. Only checks the namespaces it is given.
+pub const fn assert_unique_namespaces(namespaces : &[Namespace]) {
+    let mut index = 0;
+    while index < namespaces.len() {
+        if !is_valid_name(namespaces[index].name) {
+            panic!("Invalid namespace name. Only ASCII letters, digits and '_' are allowed.");
+        }
+        let mut other_index = index + 1;
+        while other_index < namespaces.len() {
+            if names_are_equal(namespaces[index].name, namespaces[other_index].name) {
+                panic!("Two modules claim the same namespace.");
+            }
+            if namespaces[index].hname.0 == namespaces[other_index].hname.0 {
+                panic!("Two namespaces have the same hname.");
+            }
+            other_index += 1;
+        }
+        index += 1;
+    }
+}
+
+/// Namespace of keys, so different modules of a contract don't collide on key names in state.
+#[derive(Clone, Copy, Debug)]
+pub struct Namespace {
+    name : &'static str,
+    hname : ScHname,
+}
+
+impl Namespace {
+    /// Creates a namespace with a precomputed hname. Prefer `namespaces!`, which checks namespaces at compile time.
+    pub const fn new(name : &'static str, hname : ScHname) -> Namespace {
+        Namespace { name, hname }
+    }
+
+    /// Returns the name of the namespace.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the hname of the namespace, which is part of every key.
+    pub fn hname(&self) -> ScHname {
+        ScHname(self.hname.0)
+    }
+
+    /// Creates the key 'name' in the namespace. Panics if 'name' is invalid.
+    pub fn key(&self, name : &str) -> Key {
+        if !is_valid_name(name) {
+            panic!("Invalid key name {}. Only ASCII letters, digits and '_' are allowed.", name);
+        }
+        Key(format!("{:08x}.{}", self.hname.0, name))
+    }
+}
+
+/// Creates the namespace 'name', computing its hname at runtime. Panics if 'name' is invalid.
+/// # Sample:
+/// ```ignore
+/// let balance_key : Key = keys::ns("token").key("balance").with(&agent_id);
+/// let balance : i64 = state::get_int64(&balance_key, ctx);
+/// ```
+pub fn ns(name : &'static str) -> Namespace {
+    if !is_valid_name(name) {
+        panic!("Invalid namespace name {}. Only ASCII letters, digits and '_' are allowed.", name);
+    }
+    Namespace::new(name, ScHname::new(name))
+}
+
+/// Key in a namespace. Dereferences to `&str`, so it can be used wherever a key or variable name is expected.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key(String);
+
+impl Key {
+    /// Appends 'part' to the key, e.g. the agent id a balance belongs to.
+    pub fn with<T : StateKey + ?Sized>(mut self, part : &T) -> Key {
+        let part = part.state_key();
+        self.0.push_str(&format!(".{}:{}", part.len(), part));
+        self
+    }
+
+    /// Returns the encoded key.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Key {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/**
+Declares the namespaces of a contract as constants, with hnames generated at compile time.
+Compilation fails if two namespaces have the same name or hname, or if a name is invalid.
+Only namespaces of the same invocation are checked against each other: namespaces declared in another invocation,
+or created at runtime with `ns`, may clash unnoticed. So keep a single registry per contract, listing the namespaces of every module.
+# Sample:
+```ignore
+iota_sc_utils::namespaces! {
+    pub TOKEN => "token",
+    pub GOVERNANCE => "governance",
+}
+
+fn my_sc_function(ctx : &ScFuncContext) {
+    let balance : i64 = state::get_int64(&TOKEN.key("balance").with(&ctx.caller()), ctx);
+}
+```
+*/
+#[macro_export]
+macro_rules! namespaces {
+    ($($(#[$meta:meta])* $visibility:vis $name:ident => $namespace:tt),+ $(,)?) => {
+        $(
+            $(#[$meta])*
+            $visibility const $name : $crate::keys::Namespace = {
+                #[allow(unused_imports)]
+                use $crate::wasmlib::ScHname;
+                $crate::keys::Namespace::new($namespace, $crate::generator::generate_schname!($namespace))
+            };
+        )+
+
+        const _ : () = $crate::keys::assert_unique_namespaces(&[$($name),+]);
+    };
+}
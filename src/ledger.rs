@@ -0,0 +1,164 @@
+use wasmlib::*;
+use crate::balances;
+use crate::collections::{IterableStateMap, Page, StateMap};
+use crate::getter::Getter;
+use crate::incoming;
+use crate::math::SafeMath;
+use crate::outgoing;
+use crate::state::StateReader;
+use crate::tokens::{ColorKey, Tokens};
+
+/// Returns what is left of 'balance' after debiting 'amount', or the shortfall if 'balance' is less than 'amount'.
+fn debited(balance : i64, amount : i64) -> Result<i64, i64> {
+    match balance.checked_sub(amount) {
+        Some(rest) if rest >= 0 => Ok(rest),
+        _ => Err(amount.saturating_sub(balance)),
+    }
+}
+
+/**
+Keeps the tokens each agent deposited in the contract, by color, in the contract state.
+Next to every balance it keeps the total supply of each color, which always equals the sum of the balances
+and must be backed by the tokens the contract holds. Every movement emits an event.
+Balances are stored under 'name.balances' and supplies under 'name.supply'.
+# Sample:
+```ignore
+let ledger = Ledger::new("ledger");
+// In "deposit"
+ledger.deposit_incoming(ctx);
+// In "withdraw"
+ledger.withdraw(&ScColor::IOTA, params::must_get_int64("amount", ctx), ctx);
+// In "transfer"
+ledger.transfer_internal(&params::must_get_agent_id("to", ctx), &ScColor::IOTA, params::must_get_int64("amount", ctx), ctx);
+// In "balanceOf" view
+let balance : i64 = ledger.balance_of(&params::must_get_agent_id("agent", ctx), &ScColor::IOTA, ctx);
+```
+*/
+pub struct Ledger {
+    name : String,
+    balances : StateMap<str, i64>,
+    supply : IterableStateMap<ScColor, i64>,
+}
+
+impl Ledger {
+    /// Creates a ledger stored under 'name' in the contract state.
+    pub fn new(name : &str) -> Ledger {
+        Ledger {
+            name : name.to_string(),
+            balances : StateMap::new(&format!("{}.balances", name)),
+            supply : IterableStateMap::new(&format!("{}.supply", name)),
+        }
+    }
+
+    /// Returns the name of the ledger in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn balance_key(agent_id : &ScAgentID, color : &ScColor) -> String {
+        format!("{}.{}", agent_id.to_string(), color.to_string())
+    }
+
+    /// Returns the amount of tokens of 'color' 'agent_id' holds in the ledger.
+    pub fn balance_of<TContext : StateReader>(&self, agent_id : &ScAgentID, color : &ScColor, ctx : &TContext) -> i64 {
+        self.balances.get(&Ledger::balance_key(agent_id, color), ctx).unwrap_or(0)
+    }
+
+    /// Returns the total amount of tokens of 'color' held in the ledger, i.e. the sum of every balance of that color.
+    pub fn total_supply<TContext : StateReader>(&self, color : &ScColor, ctx : &TContext) -> i64 {
+        self.supply.get(color, ctx).unwrap_or(0)
+    }
+
+    /// Returns the total supply of the colors in 'page' held in the ledger, by color. Colors are kept in the order they were first deposited.
+    pub fn supply_page<TContext : StateReader>(&self, page : Page, ctx : &TContext) -> Tokens {
+        self.supply.iter(page.offset, page.limit, ctx).map(|(color, amount)| (ColorKey(color), amount)).collect()
+    }
+
+    /// Sets the page of total supplies requested through params PARAM_OFFSET and PARAM_LIMIT as results, for a view.
+    /// Colors are set in RESULT_KEYS and their supplies in RESULT_ITEMS, at the same positions.
+    pub fn set_supply_results<TContext : StateReader + Getter>(&self, ctx : &TContext) {
+        self.supply.set_page_results(ctx);
+    }
+
+    /// Panics if the total supply of 'color' is larger than what the contract holds of it.
+    pub fn require_backed<TContext : StateReader>(&self, color : &ScColor, ctx : &TContext) {
+        let supply = self.total_supply(color, ctx);
+        let own_balance = balances::balance(color, ctx);
+        ctx.require(supply <= own_balance, &format!("Ledger {} is not backed in color {}. Supply: {}, held: {}.", self.name, color.to_string(), supply, own_balance));
+    }
+
+    fn credit(&self, agent_id : &ScAgentID, color : &ScColor, amount : i64, ctx : &ScFuncContext) {
+        self.balances.entry(&Ledger::balance_key(agent_id, color), ctx).safe_add(&amount);
+    }
+
+    fn debit(&self, agent_id : &ScAgentID, color : &ScColor, amount : i64, ctx : &ScFuncContext) {
+        ctx.require(amount > 0, &format!("Invalid amount {} of color {}. It must be positive.", amount, color.to_string()));
+        let balance = self.balance_of(agent_id, color, ctx);
+        match debited(balance, amount) {
+            Ok(rest) => self.balances.insert(&Ledger::balance_key(agent_id, color), &rest, ctx),
+            Err(shortfall) => {
+                let message = format!("Insufficient ledger balance of {} in color {}. Required: {}, available: {}, shortfall: {}.", agent_id.to_string(), color.to_string(), amount, balance, shortfall);
+                ctx.panic(&message);
+                panic!("{}", message)
+            },
+        }
+    }
+
+    fn change_supply(&self, color : &ScColor, supply : i64, ctx : &ScFuncContext) {
+        match supply {
+            0 => { self.supply.remove(color, ctx); },
+            _ => self.supply.insert(color, &supply, ctx),
+        }
+    }
+
+    /// Credits the caller with every token attached to the call. Returns the deposited tokens, by color.
+    pub fn deposit_incoming(&self, ctx : &ScFuncContext) -> Tokens {
+        let caller = ctx.caller();
+        let incoming_tokens = incoming::all(ctx);
+        for (color, amount) in incoming_tokens.iter() {
+            self.credit(&caller, color, *amount, ctx);
+            self.change_supply(color, self.total_supply(color, ctx).safe_add(amount, ctx), ctx);
+            self.require_backed(color, ctx);
+            ctx.event(&format!("ledger.deposited {} {} {} {}", self.name, caller.to_string(), color.to_string(), amount));
+        }
+        incoming_tokens
+    }
+
+    /// Debits 'amount' tokens of 'color' from the caller and sends them to it. Panics if the caller holds less than 'amount'.
+    pub fn withdraw(&self, color : &ScColor, amount : i64, ctx : &ScFuncContext) {
+        let caller = ctx.caller();
+        self.require_backed(color, ctx);
+        self.debit(&caller, color, amount, ctx);
+        self.change_supply(color, self.total_supply(color, ctx).safe_sub(&amount, ctx), ctx);
+        outgoing::send_to_agent(&caller, color, amount, ctx);
+        ctx.event(&format!("ledger.withdrawn {} {} {} {}", self.name, caller.to_string(), color.to_string(), amount));
+    }
+
+    /// Moves 'amount' tokens of 'color' from the caller to 'to' within the ledger, without sending any token.
+    /// Panics if the caller holds less than 'amount'. Total supply doesn't change.
+    pub fn transfer_internal(&self, to : &ScAgentID, color : &ScColor, amount : i64, ctx : &ScFuncContext) {
+        let caller = ctx.caller();
+        self.debit(&caller, color, amount, ctx);
+        self.credit(to, color, amount, ctx);
+        ctx.event(&format!("ledger.transferred {} {} {} {} {}", self.name, caller.to_string(), to.to_string(), color.to_string(), amount));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debit_leaves_the_rest() {
+        assert_eq!(debited(100, 40), Ok(60));
+        assert_eq!(debited(100, 100), Ok(0));
+        assert_eq!(debited(i64::MAX, 1), Ok(i64::MAX - 1));
+    }
+
+    #[test]
+    fn overdraft_returns_the_shortfall() {
+        assert_eq!(debited(0, 1), Err(1));
+        assert_eq!(debited(40, 100), Err(60));
+        assert_eq!(debited(0, i64::MAX), Err(i64::MAX));
+    }
+}
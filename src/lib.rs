@@ -8,12 +8,42 @@ pub mod math;
 
 ///  Syntax sugar to work with context parameters.
 pub mod params;
+///  Declarative validation rules for parameters, reporting every violation together.
+pub mod validate;
 ///  Syntax sugar to work with context results.
 pub mod results;
+///  Fluent builder of calls and posts to other contracts, and typed access to their results.
+pub mod calls;
 ///  Syntax sugar to work with context state.
 pub mod state;
 /// Syntax sugar to work with incoming transfers.
 pub mod incoming;
+///  Amounts of tokens by color, in ordered collections.
+pub mod tokens;
+///  Tokens held by the contract.
+pub mod balances;
+///  Transfers of the contract's own tokens to addresses, agents and other chains.
+pub mod outgoing;
+///  Proportional split of payments among payees.
+pub mod splitter;
+///  Per-function fees charged from incoming tokens into a treasury.
+pub mod fees;
+///  Tokens deposited by each agent, kept in the contract state.
+pub mod ledger;
+///  Support for reading and writing values of any supported type from/to wasmlib maps.
+pub mod values;
+///  Compact and deterministic binary encoding of custom types, so they can be stored as bytes.
+pub mod codec;
+///  Encoding of values as done by Wasp and the Go wasmlib. Runs natively, outside of the wasm vm.
+pub mod wire;
+///  Typed collections persisted in contract state.
+pub mod collections;
+///  Namespaced keys, so different modules of a contract don't collide on names in state.
+pub mod keys;
+///  Versioning of the contract state schema and migrations between versions.
+pub mod migration;
+///  Staged state changes, applied in one pass or discarded.
+pub mod transaction;
 
 ///  Keeps information relevant to standards such as TIP-100.
 pub mod interfaces;
@@ -24,6 +54,10 @@ pub mod interfaces;
 /// Github repository: `<https://github.com/brunoamancio/IOTA-SC-HName-Generator>`
 pub mod generator;
 
+/// Derive macros which map structs to context params and results, and encode custom types.
+/// Sample: `#[derive(FromParams, IntoResults, FromResults, Encode, Decode, MapValue)]`
+pub mod derive;
+
 /// Integrated tool responsible for communication with ISCP's vm. This is a modified version of the original IF's wasmlib.
 /// Differences are kept minimal and only exist if they provide simpler interface, nothing else. 
 /// Documentation: `<https://github.com/iotaledger/wasp/tree/master/contracts/rust/wasmlib/docs>`
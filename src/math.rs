@@ -108,3 +108,47 @@ macro_rules! add_impl {
 
 add_impl!(u8, u16, u32, u64, usize);
 add_impl!(i8, i16, i32, i64, isize);
+
+/// Basis points of a whole amount, e.g. shares of payees of a splitter add up to it.
+pub const BASIS_POINTS_TOTAL : i64 = 10_000;
+
+/// Returns 'basis_points' of 'amount', rounded down, or None on overflows. Doesn't need a context.
+/// Doesn't overflow for any amount, as long as 'basis_points' is at most BASIS_POINTS_TOTAL.
+pub fn checked_basis_points_of(amount : i64, basis_points : i64) -> Option<i64> {
+    // amount * basis_points / total, computed from the quotient and remainder of amount / total so it never overflows.
+    let quotient = amount / BASIS_POINTS_TOTAL;
+    let rest = amount % BASIS_POINTS_TOTAL;
+    quotient.checked_mul(basis_points)?.checked_add(rest.checked_mul(basis_points)? / BASIS_POINTS_TOTAL)
+}
+
+/// Returns 'basis_points' of 'amount', rounded down. Panics on overflows, which can only happen if 'basis_points' is more than BASIS_POINTS_TOTAL.
+pub fn basis_points_of<TContext : ScBaseContext>(amount : i64, basis_points : i64, ctx : &TContext) -> i64 {
+    match checked_basis_points_of(amount, basis_points) {
+        Some(part) => part,
+        None => {
+            let message = format!("Invalid {} basis points of {}. The result overflows.", basis_points, amount);
+            ctx.panic(&message);
+            panic!("{}", message)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basis_points_round_down() {
+        assert_eq!(checked_basis_points_of(10_000, 2_500), Some(2_500));
+        assert_eq!(checked_basis_points_of(9, 5_000), Some(4));
+        assert_eq!(checked_basis_points_of(1, 9_999), Some(0));
+        assert_eq!(checked_basis_points_of(0, 10_000), Some(0));
+    }
+
+    #[test]
+    fn basis_points_do_not_overflow_up_to_the_total() {
+        assert_eq!(checked_basis_points_of(i64::MAX, BASIS_POINTS_TOTAL), Some(i64::MAX));
+        assert_eq!(checked_basis_points_of(i64::MAX, 5_000), Some(i64::MAX / 2));
+        assert_eq!(checked_basis_points_of(i64::MAX, i64::MAX), None);
+    }
+}
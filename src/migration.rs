@@ -0,0 +1,96 @@
+use wasmlib::*;
+use crate::access;
+use crate::state::StateReader;
+
+/// Name of the state variable which keeps the schema version of the contract state.
+pub const VARIABLE_SCHEMA_VERSION : &str = "migration.schema_version";
+/// Suggested name of the func which runs pending migrations. See `Schema::migrate`.
+pub const NAME_FUNC_MIGRATE : &str = "migrate";
+
+/// Step which migrates the contract state from the previous schema version to the next one.
+pub struct Migration {
+    /// Name of the migration, used in events and error messages.
+    pub name : &'static str,
+    /// Migrates the state.
+    pub migrate : fn(&ScFuncContext),
+}
+
+/**
+Ordered migrations of the contract state. Migration N (starting at 1) migrates the state from version N-1 to N,
+so the version expected by the code is the amount of migrations. New contract versions append migrations, never change existing ones.
+# Sample:
+```ignore
+const SCHEMA : Schema = Schema::new(&[
+    Migration { name : "add_fees", migrate : migrate_add_fees },
+    Migration { name : "split_balances", migrate : migrate_split_balances },
+]);
+
+fn init(ctx : &ScFuncContext) {
+    SCHEMA.run_pending(ctx);
+}
+
+fn func_migrate(ctx : &ScFuncContext) {
+    SCHEMA.migrate(ctx);
+}
+
+fn func_transfer(ctx : &ScFuncContext) {
+    SCHEMA.require_current(ctx);
+    // ...
+}
+```
+*/
+pub struct Schema {
+    migrations : &'static [Migration],
+}
+
+impl Schema {
+    /// Creates a schema with ordered 'migrations'.
+    pub const fn new(migrations : &'static [Migration]) -> Schema {
+        Schema { migrations }
+    }
+
+    /// Returns the schema version expected by the code.
+    pub fn expected_version(&self) -> i64 {
+        self.migrations.len() as i64
+    }
+
+    /// Returns the schema version of the contract state. New contracts have version 0.
+    pub fn stored_version<TContext : StateReader>(&self, ctx : &TContext) -> i64 {
+        ctx.immutable_state().get_int64(VARIABLE_SCHEMA_VERSION).value()
+    }
+
+    /// Checks if the contract state has the version expected by the code. Returns true if it has.
+    pub fn is_current<TContext : StateReader>(&self, ctx : &TContext) -> bool {
+        self.stored_version(ctx) == self.expected_version()
+    }
+
+    /// Panics if the contract state does not have the version expected by the code. Call it at the start of every func, except the ones running migrations.
+    pub fn require_current<TContext : StateReader>(&self, ctx : &TContext) {
+        let stored_version = self.stored_version(ctx);
+        let expected_version = self.expected_version();
+        ctx.require(stored_version == expected_version, &format!("State schema version {} does not match expected version {}. Pending migrations must run first.", stored_version, expected_version));
+    }
+
+    /// Runs the migrations the contract state has not gone through yet, in order. Returns how many ran.
+    /// Meant for the init func. Panics if the state has a newer version than the code.
+    pub fn run_pending(&self, ctx : &ScFuncContext) -> i64 {
+        let stored_version = self.stored_version(ctx);
+        let expected_version = self.expected_version();
+        ctx.require(stored_version <= expected_version, &format!("State schema version {} is newer than expected version {}.", stored_version, expected_version));
+
+        let version_variable = ctx.state().get_int64(VARIABLE_SCHEMA_VERSION);
+        for (index, migration) in self.migrations.iter().enumerate().skip(stored_version as usize) {
+            (migration.migrate)(ctx);
+            let version = index as i64 + 1;
+            version_variable.set_value(version);
+            ctx.event(&format!("migration.applied {} {}", version, migration.name));
+        }
+        expected_version - stored_version
+    }
+
+    /// Runs pending migrations. Panics if caller is not the contract creator. Meant for a func such as NAME_FUNC_MIGRATE.
+    pub fn migrate(&self, ctx : &ScFuncContext) -> i64 {
+        access::caller_must_be_contract_creator(ctx);
+        self.run_pending(ctx)
+    }
+}
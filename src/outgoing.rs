@@ -0,0 +1,64 @@
+use wasmlib::*;
+use crate::balances;
+use crate::generator;
+use crate::params;
+use crate::tokens::{self, Tokens};
+
+/// Hname of the core contract "accounts", which keeps the tokens of agents on a chain.
+pub const HNAME_CORE_ACCOUNTS : ScHname = generator::generate_schname!("accounts");
+/// Hname of the function "deposit" of the core contract "accounts".
+pub const HNAME_FUNC_DEPOSIT : ScHname = generator::generate_schname!("deposit");
+/// Name of the param of "deposit" which receives the agent id credited with the tokens.
+pub const PARAM_AGENT_ID : &str = "a";
+
+/// Panics if 'amount' is not positive or the contract holds less than 'amount' tokens of 'color', naming the color and shortfall.
+pub fn require_own_balance(color : &ScColor, amount : i64, ctx : &ScFuncContext) {
+    ctx.require(amount > 0, &format!("Invalid amount {} of color {}. It must be positive.", amount, color.to_string()));
+    balances::require_balance(amount, color, ctx);
+}
+
+/// Panics if 'tokens' is empty or the contract holds less than any of its amounts.
+fn require_own_balances(tokens : &Tokens, ctx : &ScFuncContext) {
+    ctx.require(!tokens.is_empty(), "No tokens to send.");
+    for (color, amount) in tokens.iter() {
+        require_own_balance(color, *amount, ctx);
+    }
+}
+
+/// Sends 'amount' tokens of 'color' to 'address' on the tangle. Panics if the contract doesn't hold enough tokens.
+pub fn send_to_address(address : &ScAddress, color : &ScColor, amount : i64, ctx : &ScFuncContext) {
+    require_own_balance(color, amount, ctx);
+    ctx.transfer_to_address(address, ScTransfers::transfer(color, amount));
+}
+
+/// Sends 'amount' tokens of 'color' to 'agent_id': to its address on the tangle, or to its account on this chain if it is a contract.
+/// Panics if the contract doesn't hold enough tokens.
+pub fn send_to_agent(agent_id : &ScAgentID, color : &ScColor, amount : i64, ctx : &ScFuncContext) {
+    require_own_balance(color, amount, ctx);
+    transfer_to_agent(agent_id, ScTransfers::transfer(color, amount), ctx);
+}
+
+/// Sends every amount of 'tokens' to 'agent_id' at once, as in `send_to_agent`. Panics if the contract doesn't hold enough tokens of any color.
+pub fn send_multi(agent_id : &ScAgentID, tokens : &Tokens, ctx : &ScFuncContext) {
+    require_own_balances(tokens, ctx);
+    transfer_to_agent(agent_id, tokens::to_transfers(tokens, ctx), ctx);
+}
+
+fn transfer_to_agent(agent_id : &ScAgentID, transfers : ScTransfers, ctx : &ScFuncContext) {
+    if agent_id.is_address() {
+        ctx.transfer_to_address(&agent_id.address(), transfers);
+        return;
+    }
+    let deposit_params = params::new();
+    params::add_agent_id(PARAM_AGENT_ID, agent_id, &deposit_params);
+    ctx.call(HNAME_CORE_ACCOUNTS, HNAME_FUNC_DEPOSIT, Some(deposit_params), Some(transfers));
+}
+
+/// Deposits every amount of 'tokens' in the account of 'agent_id' on the chain 'chain_id', by posting to its core contract "accounts".
+/// Panics if the contract doesn't hold enough tokens of any color.
+pub fn deposit_to_chain(chain_id : &ScChainID, agent_id : &ScAgentID, tokens : &Tokens, ctx : &ScFuncContext) {
+    require_own_balances(tokens, ctx);
+    let deposit_params = params::new();
+    params::add_agent_id(PARAM_AGENT_ID, agent_id, &deposit_params);
+    ctx.post(chain_id, HNAME_CORE_ACCOUNTS, HNAME_FUNC_DEPOSIT, Some(deposit_params), tokens::to_transfers(tokens, ctx), 0);
+}
@@ -1,6 +1,12 @@
 use wasmlib::*;
 use crate::getter::Getter;
 use crate::getter::PARAMS;
+use crate::codec::{self, Decode, Encode};
+use crate::validate::{Rule, Validator};
+use crate::values::{self, ArrayError, ArrayValue, MapValue};
+
+/// Maximum amount of items of parameter arrays read with the typed array functions, e.g. `must_get_agent_id_array`.
+pub const MAX_ARRAY_LENGTH : i32 = 100;
 
 /// Creates a new ScMutableMap instance
 pub fn new() -> ScMutableMap {
@@ -25,6 +31,140 @@ add_impl_pub_fns!(PARAMS, must_get_color, get_color, exists_color, ScColor);
 add_impl_pub_fns!(PARAMS, must_get_chain_id, get_chain_id, exists_chain_id, ScChainID);
 
 
+// Encoded types
+
+/// Tries to get a parameter encoded with `codec::Encode`. Panics if it can't find it or it can't be decoded.
+pub fn must_get_encoded<T : Decode, TGetter : Getter + ScBaseContext>(parameter_name : &str, ctx : &TGetter) -> T {
+    let bytes = ctx.must_get_bytes(PARAMS, parameter_name);
+    codec::must_decode(&format!("parameter {}", parameter_name), &bytes, ctx)
+}
+
+/// Tries to get a parameter encoded with `codec::Encode`. Returns default value if it can't find it. Panics if it can't be decoded.
+pub fn get_encoded<T : Decode + Default, TGetter : Getter + ScBaseContext>(parameter_name : &str, ctx : &TGetter) -> T {
+    if !ctx.exists_bytes(PARAMS, parameter_name) {
+        return T::default();
+    }
+    let bytes = ctx.get_bytes(PARAMS, parameter_name);
+    codec::must_decode(&format!("parameter {}", parameter_name), &bytes, ctx)
+}
+
+// Validated values
+
+/// Tries to get a parameter and checks it against every rule. Panics if it can't find it or it breaks any rule, listing every violation.
+/// # Sample:
+/// ```ignore
+/// let amount : i64 = params::must_get_validated("amount", &[&validate::range(1, 1000)], ctx);
+/// ```
+pub fn must_get_validated<T : MapValue, TContext : ScBaseContext>(parameter_name : &str, rules : &[&dyn Rule<T>], ctx : &TContext) -> T {
+    let mut validator = Validator::new();
+    let value = validator.param(parameter_name, &ctx.params(), rules);
+    validator.finish(ctx);
+    value.expect("a missing or undecodable parameter is a violation, so finish panics")
+}
+
+/// Describes a struct whose fields are read from params. Use `#[derive(FromParams)]` to implement it.
+pub trait FromParams : Sized {
+    /// Reads every field from the params, checking the validation rules. Panics listing every missing or invalid field.
+    fn must_read_params<TContext : ScBaseContext>(ctx : &TContext) -> Self;
+}
+
+/// Tries to get a struct from the params. Panics listing every missing or invalid field.
+pub fn must_get_struct<T : FromParams, TContext : ScBaseContext>(ctx : &TContext) -> T {
+    T::must_read_params(ctx)
+}
+
+// Arrays
+
+/// Reads the items of the array 'key' of 'map', described as 'description'. Fails if it has more than 'max_length' items, has holes, or an item has another type.
+pub(crate) fn read_array_items<T : ArrayValue>(description : &str, key : &str, map : &ScImmutableMap, max_length : i32) -> Result<Vec<T>, String> {
+    match T::get_array_from(key, map, max_length) {
+        Ok(items) => Ok(items),
+        Err(ArrayError::TooLong { length }) => Err(format!("{} has {} items. Maximum: {}", description, length, max_length)),
+        Err(ArrayError::MissingItem { index }) => Err(format!("{} has no item at index {}", description, index)),
+        Err(ArrayError::InvalidItem { index, error }) => Err(format!("{} item {} is not a valid {}. Reason: {}", description, index, T::TYPE_NAME, error)),
+    }
+}
+
+/// Reads the items of the array 'key' of 'map'. Panics if it has more than 'max_length' items, has holes, or an item has another type.
+fn read_array<T : ArrayValue, TContext : ScBaseContext>(key : &str, map : &ScImmutableMap, max_length : i32, ctx : &TContext) -> Vec<T> {
+    match read_array_items(&format!("parameter {}", key), key, map, max_length) {
+        Ok(items) => items,
+        Err(message) => {
+            ctx.panic(&message);
+            panic!("{}", message)
+        },
+    }
+}
+
+/// Tries to get the array 'key' of 'map', e.g. a nested map parameter. Panics if it can't find it,
+/// it has more than 'max_length' items, it has holes, or an item has another type. An empty array is found.
+pub fn must_get_array_in<T : ArrayValue, TContext : ScBaseContext>(key : &str, map : &ScImmutableMap, max_length : i32, ctx : &TContext) -> Vec<T> {
+    ctx.require(T::array_exists_in(key, map), &format!("parameter {} not found", key));
+    read_array(key, map, max_length, ctx)
+}
+
+/// Tries to get the array 'key' of 'map', e.g. a nested map parameter. Returns an empty Vec if it can't find it.
+/// Panics if it has more than 'max_length' items, it has holes, or an item has another type.
+pub fn get_array_in<T : ArrayValue, TContext : ScBaseContext>(key : &str, map : &ScImmutableMap, max_length : i32, ctx : &TContext) -> Vec<T> {
+    read_array(key, map, max_length, ctx)
+}
+
+/// Tries to get a parameter array. Panics if it can't find it, it has more than 'max_length' items,
+/// it has holes, or an item has another type.
+pub fn must_get_array<T : ArrayValue, TContext : ScBaseContext>(parameter_name : &str, max_length : i32, ctx : &TContext) -> Vec<T> {
+    must_get_array_in(parameter_name, &ctx.params(), max_length, ctx)
+}
+
+/// Tries to get a parameter array. Returns an empty Vec if it can't find it.
+/// Panics if it has more than 'max_length' items, it has holes, or an item has another type.
+pub fn get_array<T : ArrayValue, TContext : ScBaseContext>(parameter_name : &str, max_length : i32, ctx : &TContext) -> Vec<T> {
+    get_array_in(parameter_name, &ctx.params(), max_length, ctx)
+}
+
+macro_rules! add_impl_array_pub_fns {
+    ($must_get_func_name:ident, $get_func_name:ident, $item_type:ty) => {
+        /// Tries to get a parameter array of at most MAX_ARRAY_LENGTH items. Panics if it can't find it, it is too long,
+        /// it has holes, or an item has another type.
+        pub fn $must_get_func_name<TContext : ScBaseContext>(parameter_name : &str, ctx : &TContext) -> Vec<$item_type> {
+            must_get_array(parameter_name, MAX_ARRAY_LENGTH, ctx)
+        }
+
+        /// Tries to get a parameter array of at most MAX_ARRAY_LENGTH items. Returns an empty Vec if it can't find it.
+        /// Panics if it is too long, it has holes, or an item has another type.
+        pub fn $get_func_name<TContext : ScBaseContext>(parameter_name : &str, ctx : &TContext) -> Vec<$item_type> {
+            get_array(parameter_name, MAX_ARRAY_LENGTH, ctx)
+        }
+    };
+}
+
+// Primitive types
+add_impl_array_pub_fns!(must_get_string_array, get_string_array, String);
+add_impl_array_pub_fns!(must_get_int64_array, get_int64_array, i64);
+add_impl_array_pub_fns!(must_get_bytes_array, get_bytes_array, Vec<u8>);
+add_impl_array_pub_fns!(must_get_bool_array, get_bool_array, bool);
+
+// ISCP Types
+add_impl_array_pub_fns!(must_get_agent_id_array, get_agent_id_array, ScAgentID);
+add_impl_array_pub_fns!(must_get_address_array, get_address_array, ScAddress);
+add_impl_array_pub_fns!(must_get_request_id_array, get_request_id_array, ScRequestID);
+add_impl_array_pub_fns!(must_get_hname_array, get_hname_array, ScHname);
+add_impl_array_pub_fns!(must_get_hash_array, get_hash_array, ScHash);
+add_impl_array_pub_fns!(must_get_color_array, get_color_array, ScColor);
+add_impl_array_pub_fns!(must_get_chain_id_array, get_chain_id_array, ScChainID);
+
+// Nested maps
+
+/// Gets a nested map parameter. Its values are read with `must_get_in`, `get_array_in` and `must_get_array_in`.
+pub fn get_map<TContext : ScBaseContext>(parameter_name : &str, ctx : &TContext) -> ScImmutableMap {
+    ctx.params().get_map(parameter_name)
+}
+
+/// Tries to get the value 'key' of 'map', e.g. a nested map parameter. Panics if it can't find it.
+pub fn must_get_in<T : MapValue, TContext : ScBaseContext>(key : &str, map : &ScImmutableMap, ctx : &TContext) -> T {
+    ctx.require(T::exists_in(key, map), &format!("parameter {} not found", key));
+    values::must_get_from(key, map, ctx)
+}
+
 // ---------------------------    Add functions    -------------------------------------
 
 macro_rules! add_impl_adder_pub_fns {
@@ -51,3 +191,50 @@ add_impl_adder_pub_fns!(add_hname, get_hname, ScHname);
 add_impl_adder_pub_fns!(add_hash, get_hash, &ScHash);
 add_impl_adder_pub_fns!(add_color, get_color, &ScColor);
 add_impl_adder_pub_fns!(add_chain_id, get_chain_id, &ScChainID);
+
+// Encoded types
+
+/// Sets a variable encoded with `codec::Encode` in MutableMap.
+pub fn add_encoded<T : Encode>(key :&str, value : &T, mutable_map : &ScMutableMap) {
+    codec::set_encoded_in(key, value, mutable_map);
+}
+
+// Arrays
+
+/// Sets an array in MutableMap, as the typed wasmlib array of its items, so it can be read with `must_get_array`.
+pub fn add_array<T : ArrayValue>(key : &str, items : &[T], mutable_map : &ScMutableMap) {
+    T::set_array_in(key, items, mutable_map);
+}
+
+macro_rules! add_impl_array_adder_pub_fns {
+    ($add_func_name:ident, $item_type:ty) => {
+        /// Sets an array in MutableMap.
+        pub fn $add_func_name(key : &str, items : &[$item_type], mutable_map : &ScMutableMap) {
+            add_array(key, items, mutable_map);
+        }
+    };
+}
+
+// Primitive types
+add_impl_array_adder_pub_fns!(add_string_array, String);
+add_impl_array_adder_pub_fns!(add_int64_array, i64);
+add_impl_array_adder_pub_fns!(add_bytes_array, Vec<u8>);
+add_impl_array_adder_pub_fns!(add_bool_array, bool);
+
+// ISCP Types
+add_impl_array_adder_pub_fns!(add_agent_id_array, ScAgentID);
+add_impl_array_adder_pub_fns!(add_address_array, ScAddress);
+add_impl_array_adder_pub_fns!(add_request_id_array, ScRequestID);
+add_impl_array_adder_pub_fns!(add_hname_array, ScHname);
+add_impl_array_adder_pub_fns!(add_hash_array, ScHash);
+add_impl_array_adder_pub_fns!(add_color_array, ScColor);
+add_impl_array_adder_pub_fns!(add_chain_id_array, ScChainID);
+
+// Nested maps
+
+/// Creates a nested map in MutableMap and returns it, so it can be filled with the add functions.
+pub fn add_map(key : &str, mutable_map : &ScMutableMap) -> ScMutableMap {
+    let map = mutable_map.get_map(key);
+    map.clear();
+    map
+}
@@ -1,4 +1,8 @@
 use wasmlib::*;
+use crate::codec::{self, Encode};
+use crate::params;
+use crate::values::{ArrayValue, IntoMapValue, MapValue};
+use crate::wire;
 
 macro_rules! add_impl_pub_setter_fns {
     ($set_func:ident, $get_func:ident, $param_type:ty) => {
@@ -20,15 +24,9 @@ pub fn set_bool<TContext : ScBaseContext>(param_name : &str, param_value : bool,
     let bool_value = to_u8(param_value);
     ctx.results().get_bytes(param_name).set_value(&bool_value);
 }
-/// converts a boolean value into a vector of u8
-fn to_u8(param_value : bool) -> Vec<u8> {
-    let mut value : Vec<u8> = Vec::new();
-    if param_value {
-        value.push(1)
-    } else {
-        value.push(0)
-    }
-    value
+/// converts a boolean value into a vector of u8, as encoded by Wasp.
+pub(crate) fn to_u8(param_value : bool) -> Vec<u8> {
+    wire::encode_bool(param_value)
 }
 
 
@@ -87,10 +85,10 @@ pub fn get_bool(param_name : &str, immutablemap : ScImmutableMap) -> bool {
     let bool_value = to_bool(param_value);
     bool_value
 }
-fn to_bool(bytes_vector : Vec<u8>) -> bool {
-    match bytes_vector.get(0) {
-        Some(byte) => *byte == 1,
-        None => panic!("Could not convert byte to bool")
+pub(crate) fn to_bool(bytes_vector : Vec<u8>) -> bool {
+    match wire::decode_bool(&bytes_vector) {
+        Ok(bool_value) => bool_value,
+        Err(error) => panic!("Could not convert bytes to bool. Reason: {}", error)
     }
 }
 
@@ -108,3 +106,133 @@ add_impl_pub_getter_fns!(must_get_hname, get_hname, exists_hname, ScHname);
 add_impl_pub_getter_fns!(must_get_hash, get_hash, exists_hash, ScHash);
 add_impl_pub_getter_fns!(must_get_color, get_color, exists_color, ScColor);
 add_impl_pub_getter_fns!(must_get_chain_id, get_chain_id, exists_chain_id, ScChainID);
+
+// ---------------------------    Generic functions    -------------------------------------
+
+/// Sets result of any type supported by `MapValue`.
+pub fn set<T : MapValue, TContext : ScBaseContext>(param_name : &str, param_value : &T, ctx : &TContext) {
+    param_value.set_in(param_name, &ctx.results());
+}
+
+/// Tries to get a parameter of any type supported by `MapValue`. Panics if it can't find it.
+pub fn must_get<T : MapValue>(param_name : &str, immutablemap : ScImmutableMap) -> T {
+    if !T::exists_in(param_name, &immutablemap) {
+        panic!("result {} not found", param_name);
+    }
+    T::get_from(param_name, &immutablemap)
+}
+
+/// Tries to get a parameter of any type supported by `MapValue`. Returns default value if it can't find it.
+pub fn get<T : MapValue>(param_name : &str, immutablemap : ScImmutableMap) -> T {
+    T::get_from(param_name, &immutablemap)
+}
+
+/// Checks if a parameter of any type supported by `MapValue` exists. Returns true if it exists.
+pub fn exists<T : MapValue>(param_name : &str, immutablemap : ScImmutableMap) -> bool {
+    T::exists_in(param_name, &immutablemap)
+}
+
+// ---------------------------    Struct functions    -------------------------------------
+
+/// Describes a struct whose fields are returned to the sc function caller. Use `#[derive(IntoResults)]` to implement it.
+pub trait IntoResults {
+    /// Sets every field in 'results'.
+    fn write_results(&self, results : &ScMutableMap);
+}
+
+/// Describes a struct whose fields are read from the results of a call. Use `#[derive(FromResults)]` to implement it.
+pub trait FromResults : Sized {
+    /// Reads every field from 'results'. Panics if it can't find any of them.
+    fn must_read_results(results : &ScImmutableMap) -> Self;
+    /// Reads every field from 'results'. Uses default values for the ones it can't find.
+    fn read_results(results : &ScImmutableMap) -> Self;
+}
+
+/// Sets every field of 'value' as a result.
+pub fn set_struct<T : IntoResults, TContext : ScBaseContext>(value : &T, ctx : &TContext) {
+    value.write_results(&ctx.results());
+}
+
+/// Tries to get a struct from the results of a call. Panics if it can't find any of its fields.
+pub fn must_get_struct<T : FromResults>(immutablemap : ScImmutableMap) -> T {
+    T::must_read_results(&immutablemap)
+}
+
+/// Tries to get a struct from the results of a call. Uses default values for the fields it can't find.
+pub fn get_struct<T : FromResults>(immutablemap : ScImmutableMap) -> T {
+    T::read_results(&immutablemap)
+}
+
+// ---------------------------    Writer    -------------------------------------
+
+/// Returns a ResultsWriter for the results of 'ctx', which works the same for funcs and views.
+pub fn writer<TContext : ScBaseContext>(ctx : &TContext) -> ResultsWriter {
+    ResultsWriter::new(ctx)
+}
+
+/**
+Write-only handle to the results of a func or view, or to a nested map of them.
+Helper functions can take it instead of the context, so they fill results without knowing the context type.
+# Sample:
+```ignore
+fn write_summary(results : &ResultsWriter, total : i64, owners : &[ScAgentID]) {
+    results.set("total", total).set_array("owners", owners);
+    let details = results.map("details");
+    details.set("updated", true);
+}
+
+fn view_summary(ctx : &ScViewContext) {
+    write_summary(&results::writer(ctx), 10, &owners);
+}
+```
+*/
+pub struct ResultsWriter {
+    results : ScMutableMap,
+}
+
+impl ResultsWriter {
+    /// Creates a writer for the results of 'ctx'.
+    pub fn new<TContext : ScBaseContext>(ctx : &TContext) -> ResultsWriter {
+        ResultsWriter { results : ctx.results() }
+    }
+
+    /// Returns the results as an ScMutableMap.
+    pub fn as_map(&self) -> &ScMutableMap {
+        &self.results
+    }
+
+    /// Sets a result of any type supported by `MapValue`, by value or by reference.
+    pub fn set<T : IntoMapValue>(&self, result_name : &str, value : T) -> &ResultsWriter {
+        value.set_into(result_name, &self.results);
+        self
+    }
+
+    /// Sets a result array, as the typed wasmlib array of its items, so it can be read with `CallResult::must_get_array`.
+    pub fn set_array<T : ArrayValue>(&self, result_name : &str, items : &[T]) -> &ResultsWriter {
+        params::add_array(result_name, items, &self.results);
+        self
+    }
+
+    /// Sets a result encoded with `codec::Encode`.
+    pub fn set_encoded<T : Encode>(&self, result_name : &str, value : &T) -> &ResultsWriter {
+        codec::set_encoded_in(result_name, value, &self.results);
+        self
+    }
+
+    /// Sets every field of 'value' as a result.
+    pub fn set_struct<T : IntoResults>(&self, value : &T) -> &ResultsWriter {
+        value.write_results(&self.results);
+        self
+    }
+
+    /// Returns a writer for the nested map 'result_name'.
+    pub fn map(&self, result_name : &str) -> ResultsWriter {
+        ResultsWriter { results : self.results.get_map(result_name) }
+    }
+
+    /// Appends a nested map to the array of maps 'result_name' and returns a writer for it, e.g. to return a list of records.
+    pub fn push_map(&self, result_name : &str) -> ResultsWriter {
+        let maps = self.results.get_map_array(result_name);
+        ResultsWriter { results : maps.get_map(maps.length()) }
+    }
+}
@@ -0,0 +1,141 @@
+use wasmlib::*;
+use crate::collections::{IterableStateMap, StateMap, MAX_PAGE_LIMIT};
+use crate::incoming;
+use crate::math::{self, SafeMath, BASIS_POINTS_TOTAL};
+use crate::outgoing;
+use crate::state::StateReader;
+use crate::tokens::Tokens;
+
+/// How the parts of a payment reach the payees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Payout {
+    /// Parts are sent to the payees right away.
+    Push,
+    /// Parts are accrued in state, and payees withdraw them later.
+    Pull,
+}
+
+/// Panics if there are more than MAX_PAGE_LIMIT payees, a share is not positive, a payee is repeated or shares don't add up to BASIS_POINTS_TOTAL.
+fn require_shares<TContext : ScBaseContext>(payees : &[(ScAgentID, i64)], ctx : &TContext) {
+    ctx.require(!payees.is_empty(), "No payees to split among.");
+    ctx.require(payees.len() <= MAX_PAGE_LIMIT as usize, &format!("Too many payees: {}. Maximum: {}.", payees.len(), MAX_PAGE_LIMIT));
+    let mut total = 0_i64;
+    for (index, (agent_id, share)) in payees.iter().enumerate() {
+        ctx.require(*share > 0, &format!("Invalid share {} of payee {}. It must be positive.", share, agent_id.to_string()));
+        ctx.require(!payees[..index].iter().any(|(other, _)| other == agent_id), &format!("Payee {} is repeated.", agent_id.to_string()));
+        total = total.safe_add(share, ctx);
+    }
+    ctx.require(total == BASIS_POINTS_TOTAL, &format!("Shares of payees must add up to {} basis points, got {}.", BASIS_POINTS_TOTAL, total));
+}
+
+/// Splits 'amount' among 'payees' proportionally to their shares, in basis points.
+/// Parts are rounded down, and the remainder goes to the first payee, so the whole amount is always distributed.
+/// Panics if the shares are invalid, like `Splitter::set_payees`. Returns the part of each payee, in the same order.
+pub fn split<TContext : ScBaseContext>(amount : i64, payees : &[(ScAgentID, i64)], ctx : &TContext) -> Vec<(ScAgentID, i64)> {
+    require_shares(payees, ctx);
+    ctx.require(amount >= 0, &format!("Invalid amount {} to split. It must not be negative.", amount));
+    let mut parts : Vec<i64> = payees.iter().map(|(_, share)| math::basis_points_of(amount, *share, ctx)).collect();
+    let distributed = parts.iter().fold(0_i64, |total, part| total.safe_add(part, ctx));
+    parts[0] = parts[0].safe_add(&amount.safe_sub(&distributed, ctx), ctx);
+    payees.iter().zip(parts).map(|((agent_id, _), part)| (ScAgentID::from_bytes(agent_id.to_bytes()), part)).collect()
+}
+
+/// Splits incoming payments among payees configured in the contract state, proportionally to their shares in basis points.
+/// Payees and shares are stored under 'name.payees', and accrued parts (pull payouts) under 'name.owed'.
+/// # Sample:
+/// ```ignore
+/// let splitter = Splitter::new("royalties");
+/// splitter.set_payees(&[(artist, 7_000), (gallery, 3_000)], ctx);
+/// splitter.split_incoming(Payout::Pull, ctx);
+/// // Later, called by a payee
+/// splitter.withdraw(&ScColor::IOTA, ctx);
+/// ```
+pub struct Splitter {
+    name : String,
+    payees : IterableStateMap<ScAgentID, i64>,
+    owed : StateMap<str, i64>,
+}
+
+impl Splitter {
+    /// Creates a splitter stored under 'name' in the contract state.
+    pub fn new(name : &str) -> Splitter {
+        Splitter {
+            name : name.to_string(),
+            payees : IterableStateMap::new(&format!("{}.payees", name)),
+            owed : StateMap::new(&format!("{}.owed", name)),
+        }
+    }
+
+    /// Returns the name of the splitter in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn owed_key(agent_id : &ScAgentID, color : &ScColor) -> String {
+        format!("{}.{}", agent_id.to_string(), color.to_string())
+    }
+
+    /// Replaces the payees and their shares. Panics if there are more than MAX_PAGE_LIMIT payees, a share is not positive, a payee is repeated or shares don't add up to BASIS_POINTS_TOTAL.
+    /// Parts already accrued to former payees are kept, so they can still withdraw them.
+    pub fn set_payees(&self, payees : &[(ScAgentID, i64)], ctx : &ScFuncContext) {
+        require_shares(payees, ctx);
+
+        let former_payees : Vec<ScAgentID> = self.payees(ctx).into_iter().map(|(agent_id, _)| agent_id).collect();
+        for agent_id in former_payees {
+            self.payees.remove(&agent_id, ctx);
+        }
+        for (agent_id, share) in payees {
+            self.payees.insert(agent_id, share, ctx);
+        }
+    }
+
+    /// Returns the payees and their shares, in the order they were set.
+    pub fn payees<TContext : StateReader>(&self, ctx : &TContext) -> Vec<(ScAgentID, i64)> {
+        match self.payees.len(ctx) {
+            0 => Vec::new(),
+            count => self.payees.iter(0, count, ctx).collect(),
+        }
+    }
+
+    /// Splits 'amount' tokens of 'color' held by the contract among the payees and pays them out. Returns the part of each payee.
+    pub fn split(&self, amount : i64, color : &ScColor, payout : Payout, ctx : &ScFuncContext) -> Vec<(ScAgentID, i64)> {
+        let parts = split(amount, &self.payees(ctx), ctx);
+        for (agent_id, part) in parts.iter().filter(|(_, part)| *part > 0) {
+            match payout {
+                Payout::Push => {
+                    outgoing::send_to_agent(agent_id, color, *part, ctx);
+                    ctx.event(&format!("splitter.paid {} {} {} {}", self.name, agent_id.to_string(), color.to_string(), part));
+                },
+                Payout::Pull => {
+                    self.owed.entry(&Splitter::owed_key(agent_id, color), ctx).safe_add(part);
+                    ctx.event(&format!("splitter.accrued {} {} {} {}", self.name, agent_id.to_string(), color.to_string(), part));
+                },
+            }
+        }
+        parts
+    }
+
+    /// Splits every token attached to the call among the payees and pays them out. Returns the split tokens.
+    pub fn split_incoming(&self, payout : Payout, ctx : &ScFuncContext) -> Tokens {
+        let incoming_tokens = incoming::all(ctx);
+        for (color, amount) in incoming_tokens.iter() {
+            self.split(*amount, color, payout, ctx);
+        }
+        incoming_tokens
+    }
+
+    /// Returns the amount of tokens of 'color' accrued to 'agent_id' and not withdrawn yet.
+    pub fn owed<TContext : StateReader>(&self, agent_id : &ScAgentID, color : &ScColor, ctx : &TContext) -> i64 {
+        self.owed.get(&Splitter::owed_key(agent_id, color), ctx).unwrap_or(0)
+    }
+
+    /// Sends the caller every token of 'color' accrued to it. Panics if there is none. Returns the withdrawn amount.
+    pub fn withdraw(&self, color : &ScColor, ctx : &ScFuncContext) -> i64 {
+        let caller = ctx.caller();
+        let amount = self.owed.remove(&Splitter::owed_key(&caller, color), ctx).unwrap_or(0);
+        ctx.require(amount > 0, &format!("Nothing of color {} owed to {}.", color.to_string(), caller.to_string()));
+        outgoing::send_to_agent(&caller, color, amount, ctx);
+        ctx.event(&format!("splitter.withdrawn {} {} {} {}", self.name, caller.to_string(), color.to_string(), amount));
+        amount
+    }
+}
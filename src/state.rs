@@ -1,6 +1,29 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
 use wasmlib::*;
 use crate::getter::Getter;
 use crate::getter::STATE;
+use crate::codec::{self, Decode, Encode};
+use crate::math::SafeMath;
+
+/// Gives read access to the state of both func and view contexts.
+pub trait StateReader : ScBaseContext {
+    /// Returns the state as an ScImmutableMap.
+    fn immutable_state(&self) -> ScImmutableMap;
+}
+
+impl StateReader for ScFuncContext {
+    fn immutable_state(&self) -> ScImmutableMap {
+        self.state().immutable()
+    }
+}
+
+impl StateReader for ScViewContext {
+    fn immutable_state(&self) -> ScImmutableMap {
+        self.state()
+    }
+}
 
 // Primitive types
 add_impl_pub_fns!(STATE, must_get_string, get_string, exists_string, String);
@@ -15,3 +38,178 @@ add_impl_pub_fns!(STATE, must_get_hname, get_hname, exists_hname, ScHname);
 add_impl_pub_fns!(STATE, must_get_hash, get_hash, exists_hash, ScHash);
 add_impl_pub_fns!(STATE, must_get_color, get_color, exists_color, ScColor);
 add_impl_pub_fns!(STATE, must_get_chain_id, get_chain_id, exists_chain_id, ScChainID);
+
+// Encoded types
+
+/// Tries to get a variable encoded with `codec::Encode`. Panics if it can't find it or it can't be decoded.
+pub fn must_get_encoded<T : Decode, TGetter : Getter + ScBaseContext>(variable_name : &str, ctx : &TGetter) -> T {
+    let bytes = ctx.must_get_bytes(STATE, variable_name);
+    codec::must_decode(&format!("variable {}", variable_name), &bytes, ctx)
+}
+
+/// Tries to get a variable encoded with `codec::Encode`. Returns default value if it can't find it. Panics if it can't be decoded.
+pub fn get_encoded<T : Decode + Default, TGetter : Getter + ScBaseContext>(variable_name : &str, ctx : &TGetter) -> T {
+    if !ctx.exists_bytes(STATE, variable_name) {
+        return T::default();
+    }
+    let bytes = ctx.get_bytes(STATE, variable_name);
+    codec::must_decode(&format!("variable {}", variable_name), &bytes, ctx)
+}
+
+/// Sets a variable encoded with `codec::Encode`.
+pub fn set_encoded<T : Encode>(variable_name : &str, value : &T, ctx : &ScFuncContext) {
+    codec::set_encoded_in(variable_name, value, &ctx.state());
+}
+
+// Counters
+
+/// Adds 'amount' to the int64 variable 'variable_name' (0 if it doesn't exist) and returns the new value.
+/// Panics on overflows, or with the variable name if 'amount' is negative.
+pub fn add_to(variable_name : &str, amount : i64, ctx : &ScFuncContext) -> i64 {
+    StateCounter::<i64>::new(variable_name).add(amount, ctx)
+}
+
+/// Subtracts 'amount' from the int64 variable 'variable_name' (0 if it doesn't exist) and returns the new value.
+/// Panics with the variable name if the value would go below zero or if 'amount' is negative.
+pub fn sub_from(variable_name : &str, amount : i64, ctx : &ScFuncContext) -> i64 {
+    StateCounter::<i64>::new(variable_name).sub(amount, ctx)
+}
+
+/// Adds 1 to the int64 variable 'variable_name' and returns the new value. Panics on overflows.
+pub fn increment(variable_name : &str, ctx : &ScFuncContext) -> i64 {
+    add_to(variable_name, 1, ctx)
+}
+
+/// Subtracts 1 from the int64 variable 'variable_name' and returns the new value. Panics with the variable name if it would go below zero.
+pub fn decrement(variable_name : &str, ctx : &ScFuncContext) -> i64 {
+    sub_from(variable_name, 1, ctx)
+}
+
+/// Integer which can be kept in an int64 state variable. Unsigned 64 bits integers are stored with the same bits,
+/// so values above i64::MAX read as negative through `state::get_int64`.
+pub trait CounterValue : SafeMath + Copy + Default + PartialOrd + fmt::Display {
+    /// Converts the value to the int64 stored in state.
+    fn to_int64(self) -> i64;
+    /// Converts the int64 stored in state to the value. Returns None if it doesn't fit.
+    fn from_int64(value : i64) -> Option<Self>;
+}
+
+macro_rules! add_impl_counter_value {
+    ($($t:ty),+) => {
+        $(
+            impl CounterValue for $t {
+                fn to_int64(self) -> i64 {
+                    self as i64
+                }
+
+                fn from_int64(value : i64) -> Option<$t> {
+                    <$t>::try_from(value).ok()
+                }
+            }
+        )+
+    };
+}
+
+add_impl_counter_value!(u8, u16, u32, i8, i16, i32, i64, isize);
+
+impl CounterValue for u64 {
+    fn to_int64(self) -> i64 {
+        self as i64
+    }
+
+    fn from_int64(value : i64) -> Option<u64> {
+        Some(value as u64)
+    }
+}
+
+impl CounterValue for usize {
+    fn to_int64(self) -> i64 {
+        self as u64 as i64
+    }
+
+    fn from_int64(value : i64) -> Option<usize> {
+        usize::try_from(value as u64).ok()
+    }
+}
+
+/// Counter kept in the int64 state variable 'name'. Changes use `SafeMath`, so they panic on overflows.
+/// Values never go below zero, which panics with the variable name. Optionally emits the event "counter.changed <name> <value>" on every change.
+/// # Sample:
+/// ```ignore
+/// let total_supply : StateCounter<u64> = StateCounter::new("total_supply").with_events();
+/// total_supply.add(1000, ctx);
+/// let remaining : u64 = total_supply.sub(10, ctx);
+/// ```
+pub struct StateCounter<T : CounterValue> {
+    name : String,
+    emit_events : bool,
+    _type : PhantomData<T>,
+}
+
+impl<T : CounterValue> StateCounter<T> {
+    /// Creates a counter stored under 'name' in the contract state.
+    pub fn new(name : &str) -> StateCounter<T> {
+        StateCounter { name : name.to_string(), emit_events : false, _type : PhantomData }
+    }
+
+    /// Emits an event on every change of the counter.
+    pub fn with_events(mut self) -> StateCounter<T> {
+        self.emit_events = true;
+        self
+    }
+
+    /// Returns the name of the counter in the contract state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the value of the counter. Returns 0 if it doesn't exist. Panics if the stored value doesn't fit in T.
+    pub fn get<TContext : StateReader>(&self, ctx : &TContext) -> T {
+        let stored_value = ctx.immutable_state().get_int64(self.name.as_str()).value();
+        match T::from_int64(stored_value) {
+            Some(value) => value,
+            None => {
+                let message = format!("variable {} has value {}, which is out of range", self.name, stored_value);
+                ctx.panic(&message);
+                panic!("{}", message)
+            },
+        }
+    }
+
+    /// Sets the value of the counter. Panics if 'value' is below zero.
+    pub fn set(&self, value : T, ctx : &ScFuncContext) {
+        ctx.require(value >= T::default(), &format!("variable {} can't go below zero", self.name));
+        ctx.state().get_int64(self.name.as_str()).set_value(value.to_int64());
+        if self.emit_events {
+            ctx.event(&format!("counter.changed {} {}", self.name, value));
+        }
+    }
+
+    /// Adds 'amount' and returns the new value. Panics on overflows or if 'amount' is below zero.
+    pub fn add(&self, amount : T, ctx : &ScFuncContext) -> T {
+        ctx.require(amount >= T::default(), &format!("amount added to variable {} can't be negative", self.name));
+        let value = self.get(ctx).safe_add(&amount, ctx);
+        self.set(value, ctx);
+        value
+    }
+
+    /// Subtracts 'amount' and returns the new value. Panics if the value would go below zero or if 'amount' is below zero.
+    pub fn sub(&self, amount : T, ctx : &ScFuncContext) -> T {
+        ctx.require(amount >= T::default(), &format!("amount subtracted from variable {} can't be negative", self.name));
+        let current_value = self.get(ctx);
+        ctx.require(current_value >= amount, &format!("variable {} can't go below zero: {} - {}", self.name, current_value, amount));
+        let value = current_value.safe_sub(&amount, ctx);
+        self.set(value, ctx);
+        value
+    }
+
+    /// Adds 1 and returns the new value. Panics on overflows.
+    pub fn increment(&self, ctx : &ScFuncContext) -> T {
+        self.add(T::from_int64(1).unwrap(), ctx)
+    }
+
+    /// Subtracts 1 and returns the new value. Panics if the value would go below zero.
+    pub fn decrement(&self, ctx : &ScFuncContext) -> T {
+        self.sub(T::from_int64(1).unwrap(), ctx)
+    }
+}
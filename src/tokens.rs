@@ -0,0 +1,90 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use wasmlib::*;
+use crate::math::SafeMath;
+
+/// Color which can be used as key of ordered collections, such as BTreeMap. Colors are ordered by their bytes.
+/// Needed because ScColor does not implement Ord.
+pub struct ColorKey(pub ScColor);
+
+impl ColorKey {
+    /// Returns the wrapped color.
+    pub fn color(&self) -> &ScColor {
+        &self.0
+    }
+}
+
+impl Deref for ColorKey {
+    type Target = ScColor;
+
+    fn deref(&self) -> &ScColor {
+        &self.0
+    }
+}
+
+impl From<ScColor> for ColorKey {
+    fn from(color : ScColor) -> ColorKey {
+        ColorKey(color)
+    }
+}
+
+impl From<&ScColor> for ColorKey {
+    fn from(color : &ScColor) -> ColorKey {
+        ColorKey(ScColor::from_bytes(color.to_bytes()))
+    }
+}
+
+impl Clone for ColorKey {
+    fn clone(&self) -> ColorKey {
+        ColorKey::from(&self.0)
+    }
+}
+
+impl PartialEq for ColorKey {
+    fn eq(&self, other : &ColorKey) -> bool {
+        self.0.to_bytes() == other.0.to_bytes()
+    }
+}
+
+impl Eq for ColorKey {}
+
+impl PartialOrd for ColorKey {
+    fn partial_cmp(&self, other : &ColorKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ColorKey {
+    fn cmp(&self, other : &ColorKey) -> Ordering {
+        self.0.to_bytes().cmp(other.0.to_bytes())
+    }
+}
+
+/// Amounts of tokens by color.
+pub type Tokens = BTreeMap<ColorKey, i64>;
+
+/// Returns the sum of every amount in 'tokens'. Panics on overflows.
+pub fn total<TContext : ScBaseContext>(tokens : &Tokens, ctx : &TContext) -> i64 {
+    tokens.values().fold(0_i64, |total, amount| total.safe_add(amount, ctx))
+}
+
+/// Returns the amount of 'color' in 'tokens', or 0 if it has none.
+pub fn amount_of(tokens : &Tokens, color : &ScColor) -> i64 {
+    tokens.get(&ColorKey::from(color)).copied().unwrap_or(0)
+}
+
+/// Returns the sum of every amount in 'tokens', or None on overflows. Doesn't need a context.
+pub fn checked_total(tokens : &Tokens) -> Option<i64> {
+    tokens.values().try_fold(0_i64, |total, amount| total.checked_add(*amount))
+}
+
+/// Converts 'tokens' into transfers. Panics if any amount is not positive.
+pub fn to_transfers<TContext : ScBaseContext>(tokens : &Tokens, ctx : &TContext) -> ScTransfers {
+    let transfers = ScTransfers::new();
+    for (color, amount) in tokens.iter() {
+        ctx.require(*amount > 0, &format!("Invalid amount {} of color {}. It must be positive.", amount, color.to_string()));
+        transfers.set(color, *amount);
+    }
+    transfers
+}
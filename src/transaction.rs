@@ -0,0 +1,174 @@
+use std::fmt;
+use wasmlib::*;
+use crate::state::StateReader;
+use crate::values::{self, MapValue};
+
+/// Change recorded by a StateTransaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// Variable 'name' is set to the described value.
+    Set { name : String, value : String },
+    /// Nested map 'name' is cleared.
+    Delete { name : String },
+}
+
+impl Change {
+    /// Returns the name of the changed variable or nested map.
+    pub fn name(&self) -> &str {
+        match self {
+            Change::Set { name, .. } => name,
+            Change::Delete { name } => name,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Set { name, value } => write!(f, "set {} = {}", name, value),
+            Change::Delete { name } => write!(f, "delete {}", name),
+        }
+    }
+}
+
+/// Change waiting to be committed. Values are kept in a scratch map, so any MapValue can be staged.
+/// Variables and nested maps with the same name are different entries of the state, so a Set and a Delete of one name are kept apart.
+enum Staged {
+    Set { name : String, type_name : &'static str, value : String, copy : fn(&str, &ScImmutableMap, &ScMutableMap) },
+    Delete { name : String },
+}
+
+impl Staged {
+    fn name(&self) -> &str {
+        match self {
+            Staged::Set { name, .. } => name,
+            Staged::Delete { name } => name,
+        }
+    }
+
+    /// Checks if 'other' changes the same entry of the state. Returns true if it does.
+    fn replaces(&self, other : &Staged) -> bool {
+        self.name() == other.name() && std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+fn copy_value<T : MapValue>(name : &str, from : &ScImmutableMap, to : &ScMutableMap) {
+    T::get_from(name, from).set_in(name, to);
+}
+
+/// Buffers changes of the contract state, so they are applied in one pass only if every check passes.
+/// Reads through the transaction see staged changes first. Dropping it discards the changes (rollback).
+/// Also works in views, to compute what a call would change.
+/// Only the last change of each variable or nested map is kept.
+/// Single variables can't be removed from the state, so only nested maps can be deleted. Deleting doesn't change reads of variables.
+/// # Sample:
+/// ```ignore
+/// let mut transaction = StateTransaction::new();
+/// let balance : i64 = transaction.get("balance", ctx);
+/// transaction.set("balance", &(balance - amount));
+/// transaction.delete("pending_withdrawals");
+/// ctx.require(amount <= limit, "amount above limit");
+/// transaction.commit(ctx);
+/// ```
+pub struct StateTransaction {
+    buffer : ScMutableMap,
+    staged : Vec<Staged>,
+}
+
+impl StateTransaction {
+    /// Creates an empty transaction.
+    pub fn new() -> StateTransaction {
+        StateTransaction { buffer : ScMutableMap::new(), staged : Vec::new() }
+    }
+
+    /// Returns the staged type of the variable 'name'. Returns None if it wasn't set.
+    fn staged_type(&self, name : &str) -> Option<&'static str> {
+        self.staged.iter().find_map(|staged| match staged {
+            Staged::Set { name : staged_name, type_name, .. } if staged_name == name => Some(*type_name),
+            _ => None,
+        })
+    }
+
+    fn stage(&mut self, staged : Staged) {
+        self.staged.retain(|other| !other.replaces(&staged));
+        self.staged.push(staged);
+    }
+
+    /// Stages setting the variable 'name' to 'value'.
+    pub fn set<T : MapValue>(&mut self, name : &str, value : &T) {
+        value.set_in(name, &self.buffer);
+        self.stage(Staged::Set { name : name.to_string(), type_name : std::any::type_name::<T>(), value : value.describe(), copy : copy_value::<T> });
+    }
+
+    /// Stages clearing the nested map 'name', e.g. a slot or a collection.
+    /// Doesn't apply to a variable 'name', which keeps its staged or stored value.
+    pub fn delete(&mut self, name : &str) {
+        self.stage(Staged::Delete { name : name.to_string() });
+    }
+
+    /// Gets the variable 'name', staged or in state. Returns default value if it can't find it.
+    /// Panics if it was staged with another type.
+    pub fn get<T : MapValue, TContext : StateReader>(&self, name : &str, ctx : &TContext) -> T {
+        match self.staged_type(name) {
+            Some(type_name) => {
+                self.require_type::<T, TContext>(name, type_name, ctx);
+                values::must_get_from(name, &self.buffer.immutable(), ctx)
+            },
+            None => values::must_get_from(name, &ctx.immutable_state(), ctx),
+        }
+    }
+
+    /// Gets the variable 'name', staged or in state. Panics if it can't find it or it was staged with another type.
+    pub fn must_get<T : MapValue, TContext : StateReader>(&self, name : &str, ctx : &TContext) -> T {
+        if !self.exists::<T, TContext>(name, ctx) {
+            let message = format!("variable {} not found", name);
+            ctx.panic(&message);
+            panic!("{}", message)
+        }
+        self.get(name, ctx)
+    }
+
+    /// Checks if the variable 'name' exists, staged or in state. Returns true if it exists.
+    pub fn exists<T : MapValue, TContext : StateReader>(&self, name : &str, ctx : &TContext) -> bool {
+        match self.staged_type(name) {
+            Some(_) => true,
+            None => T::exists_in(name, &ctx.immutable_state()),
+        }
+    }
+
+    fn require_type<T : MapValue, TContext : StateReader>(&self, name : &str, type_name : &str, ctx : &TContext) {
+        let read_type_name = std::any::type_name::<T>();
+        ctx.require(type_name == read_type_name, &format!("variable {} was staged as {}, but read as {}", name, type_name, read_type_name));
+    }
+
+    /// Checks if there are no staged changes. Returns true if it is empty.
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty()
+    }
+
+    /// Returns the staged changes, in the order they will be applied.
+    pub fn changes(&self) -> Vec<Change> {
+        self.staged.iter().map(|staged| match staged {
+            Staged::Set { name, value, .. } => Change::Set { name : name.clone(), value : value.clone() },
+            Staged::Delete { name } => Change::Delete { name : name.clone() },
+        }).collect()
+    }
+
+    /// Applies the staged changes to the state, in one pass.
+    pub fn commit(self, ctx : &ScFuncContext) {
+        let state = ctx.state();
+        let buffer = self.buffer.immutable();
+        for staged in &self.staged {
+            match staged {
+                Staged::Set { name, copy, .. } => copy(name, &buffer, &state),
+                Staged::Delete { name } => state.get_map(name.as_str()).clear(),
+            }
+        }
+    }
+}
+
+impl Default for StateTransaction {
+    fn default() -> StateTransaction {
+        StateTransaction::new()
+    }
+}
@@ -0,0 +1,317 @@
+use std::fmt;
+use wasmlib::*;
+use crate::codec::DecodeError;
+use crate::values::MapValue;
+
+/// Rule a value must follow.
+pub trait Rule<T : ?Sized> {
+    /// Checks 'value'. Returns the description of the violation if it doesn't follow the rule, e.g. "must not be empty".
+    fn check(&self, value : &T) -> Result<(), String>;
+}
+
+/// Value whose length can be validated, e.g. strings, bytes and arrays.
+pub trait Length {
+    /// Returns the length. Bytes for strings, items for arrays.
+    fn length(&self) -> usize;
+}
+
+impl Length for str {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+// ---------------------------    Rules    -------------------------------------
+
+/// Rule of `range`.
+pub struct Range<T> {
+    min : T,
+    max : T,
+}
+
+/// Value must be between 'min' and 'max', both included.
+pub fn range<T : PartialOrd + fmt::Display>(min : T, max : T) -> Range<T> {
+    Range { min, max }
+}
+
+impl<T : PartialOrd + fmt::Display> Rule<T> for Range<T> {
+    fn check(&self, value : &T) -> Result<(), String> {
+        if *value < self.min || *value > self.max {
+            return Err(format!("must be between {} and {}, got {}", self.min, self.max, value));
+        }
+        Ok(())
+    }
+}
+
+/// Rule of `min_len`.
+pub struct MinLen(usize);
+
+/// Length must be at least 'min'.
+pub fn min_len(min : usize) -> MinLen {
+    MinLen(min)
+}
+
+impl<T : Length + ?Sized> Rule<T> for MinLen {
+    fn check(&self, value : &T) -> Result<(), String> {
+        if value.length() < self.0 {
+            return Err(format!("length must be at least {}, got {}", self.0, value.length()));
+        }
+        Ok(())
+    }
+}
+
+/// Rule of `max_len`.
+pub struct MaxLen(usize);
+
+/// Length must be at most 'max'.
+pub fn max_len(max : usize) -> MaxLen {
+    MaxLen(max)
+}
+
+impl<T : Length + ?Sized> Rule<T> for MaxLen {
+    fn check(&self, value : &T) -> Result<(), String> {
+        if value.length() > self.0 {
+            return Err(format!("length must be at most {}, got {}", self.0, value.length()));
+        }
+        Ok(())
+    }
+}
+
+/// Rule of `one_of`.
+pub struct OneOf<'a, TAllowed> {
+    allowed : &'a [TAllowed],
+}
+
+/// Value must be equal to one of 'allowed', e.g. an hname in a set of known functions.
+pub fn one_of<TAllowed>(allowed : &[TAllowed]) -> OneOf<'_, TAllowed> {
+    OneOf { allowed }
+}
+
+impl<'a, T : PartialEq<TAllowed> + ?Sized, TAllowed> Rule<T> for OneOf<'a, TAllowed> {
+    fn check(&self, value : &T) -> Result<(), String> {
+        if !self.allowed.iter().any(|allowed| value == allowed) {
+            return Err(format!("must be one of the {} allowed values", self.allowed.len()));
+        }
+        Ok(())
+    }
+}
+
+/// Rule of `not_zero_hash`.
+pub struct NotZeroHash;
+
+/// Hash must not be all zeros, which is the default value of a missing hash.
+pub fn not_zero_hash() -> NotZeroHash {
+    NotZeroHash
+}
+
+impl Rule<ScHash> for NotZeroHash {
+    fn check(&self, value : &ScHash) -> Result<(), String> {
+        if value.to_bytes().iter().all(|byte| *byte == 0) {
+            return Err("must not be the zero hash".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Rule of `not_caller`.
+pub struct NotCaller(ScAgentID);
+
+/// Agent must not be the caller of the request, e.g. a recipient that can't be the sender.
+pub fn not_caller(ctx : &ScFuncContext) -> NotCaller {
+    NotCaller(ctx.caller())
+}
+
+impl Rule<ScAgentID> for NotCaller {
+    fn check(&self, value : &ScAgentID) -> Result<(), String> {
+        if *value == self.0 {
+            return Err("must not be the caller".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Rule of `custom`.
+pub struct Custom<F>(F);
+
+/// Value must pass 'check', which returns the description of the violation otherwise.
+/// # Sample:
+/// ```ignore
+/// let owner = ctx.contract_creator();
+/// let not_owner = validate::custom(|agent_id : &ScAgentID| match *agent_id == owner {
+///     true => Err("must not be the owner".to_string()),
+///     false => Ok(()),
+/// });
+/// ```
+/// With `#[derive(FromParams)]`, `#[params(custom_ctx = "path::to::check")]` also passes the context to the check,
+/// e.g. `fn not_owner<TContext : ScBaseContext>(agent_id : &ScAgentID, ctx : &TContext) -> Result<(), String>`.
+pub fn custom<T : ?Sized, F : Fn(&T) -> Result<(), String>>(check : F) -> Custom<F> {
+    Custom(check)
+}
+
+impl<T : ?Sized, F : Fn(&T) -> Result<(), String>> Rule<T> for Custom<F> {
+    fn check(&self, value : &T) -> Result<(), String> {
+        (self.0)(value)
+    }
+}
+
+// ---------------------------    Validator    -------------------------------------
+
+/// Rule a parameter doesn't follow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Name of the parameter.
+    pub parameter_name : String,
+    /// Description of the violation, e.g. "must be between 1 and 100, got 0".
+    pub message : String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "parameter {} {}", self.parameter_name, self.message)
+    }
+}
+
+/**
+Collects the violations of several parameters, so they are reported together.
+# Sample:
+```ignore
+let mut validator = Validator::new();
+let amount : i64 = validator.check("amount", params::get_int64("amount", ctx), &[&validate::range(1, 1000)]);
+let name : Option<String> = validator.param("name", &ctx.params(), &[&validate::min_len(3), &validate::max_len(32)]);
+// Panics with every violation, e.g. "Invalid parameters: parameter amount must be between 1 and 1000, got 0; parameter name not found"
+validator.finish(ctx);
+```
+*/
+#[derive(Default)]
+pub struct Validator {
+    violations : Vec<Violation>,
+}
+
+impl Validator {
+    /// Creates a validator without violations.
+    pub fn new() -> Validator {
+        Validator { violations : Vec::new() }
+    }
+
+    /// Records a violation of the parameter 'parameter_name'.
+    pub fn add_violation(&mut self, parameter_name : &str, message : &str) {
+        self.violations.push(Violation { parameter_name : parameter_name.to_string(), message : message.to_string() });
+    }
+
+    /// Checks 'value' of the parameter 'parameter_name' against every rule, recording the violations. Returns 'value'.
+    pub fn check<T>(&mut self, parameter_name : &str, value : T, rules : &[&dyn Rule<T>]) -> T {
+        for rule in rules {
+            if let Err(message) = rule.check(&value) {
+                self.add_violation(parameter_name, &message);
+            }
+        }
+        value
+    }
+
+    /// Reads the parameter 'key' of 'params' and checks it against every rule. Records a violation if it can't find it or it can't be decoded.
+    /// Returns None if it can't find it or it can't be decoded.
+    pub fn param<T : MapValue>(&mut self, key : &str, params : &ScImmutableMap, rules : &[&dyn Rule<T>]) -> Option<T> {
+        let value = self.read(key, Validator::find(key, params), true)?;
+        Some(self.check(key, value, rules))
+    }
+
+    /// Reads the parameter 'key' of 'params' and checks it against every rule if it exists. Records a violation if it can't be decoded.
+    /// Returns None if it can't find it or it can't be decoded.
+    pub fn optional_param<T : MapValue>(&mut self, key : &str, params : &ScImmutableMap, rules : &[&dyn Rule<T>]) -> Option<T> {
+        let value = self.read(key, Validator::find(key, params), false)?;
+        Some(self.check(key, value, rules))
+    }
+
+    /// Decodes the parameter 'key' of 'params' only if it exists. Returns None if it can't find it.
+    fn find<T : MapValue>(key : &str, params : &ScImmutableMap) -> Option<Result<T, DecodeError>> {
+        match T::exists_in(key, params) {
+            true => Some(T::try_get_from(key, params)),
+            false => None,
+        }
+    }
+
+    /// Returns the value 'found' for the parameter 'key', recording a violation if it can't be decoded, or is missing and 'required'.
+    fn read<T>(&mut self, key : &str, found : Option<Result<T, DecodeError>>, required : bool) -> Option<T> {
+        match found {
+            Some(Ok(value)) => Some(value),
+            Some(Err(error)) => {
+                self.add_violation(key, &format!("could not be decoded. Reason: {}", error));
+                None
+            },
+            None => {
+                if required {
+                    self.add_violation(key, "not found");
+                }
+                None
+            },
+        }
+    }
+
+    /// Checks if no violation was recorded. Returns true if there was none.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns the recorded violations.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Panics listing every recorded violation, if there is any.
+    pub fn finish<TContext : ScBaseContext>(&self, ctx : &TContext) {
+        if self.is_valid() {
+            return;
+        }
+        let violations : Vec<String> = self.violations.iter().map(|violation| violation.to_string()).collect();
+        let message = format!("Invalid parameters: {}", violations.join("; "));
+        ctx.panic(&message);
+        panic!("{}", message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::Encoded;
+
+    #[test]
+    fn missing_optional_encoded_params_are_not_violations() {
+        let mut validator = Validator::new();
+        assert_eq!(validator.read::<Encoded<Vec<i64>>>("amounts", None, false), None);
+        assert!(validator.is_valid());
+    }
+
+    #[test]
+    fn missing_required_params_are_violations() {
+        let mut validator = Validator::new();
+        assert_eq!(validator.read::<Encoded<Vec<i64>>>("amounts", None, true), None);
+        assert_eq!(validator.violations()[0].to_string(), "parameter amounts not found");
+    }
+
+    #[test]
+    fn undecodable_params_are_violations() {
+        let mut validator = Validator::new();
+        assert_eq!(validator.read::<Encoded<Vec<i64>>>("amounts", Some(Err(DecodeError::InvalidInteger)), false), None);
+        assert_eq!(validator.violations().len(), 1);
+        assert_eq!(validator.read("amounts", Some(Ok(Encoded(vec![1_i64]))), true), Some(Encoded(vec![1_i64])));
+        assert_eq!(validator.violations().len(), 1);
+    }
+}
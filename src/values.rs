@@ -0,0 +1,245 @@
+use wasmlib::*;
+use wasmlib::host;
+use crate::codec::DecodeError;
+use crate::wire::WireValue;
+
+/// Defines how a type is written to and read from wasmlib maps, such as params, state and results.
+pub trait MapValue : Sized {
+    /// Sets the value under 'key' in 'mutable_map'.
+    fn set_in(&self, key : &str, mutable_map : &ScMutableMap);
+    /// Gets the value under 'key' in 'immutable_map'. Returns default value if it can't find it.
+    fn get_from(key : &str, immutable_map : &ScImmutableMap) -> Self;
+    /// Same as `get_from`, returning the reason if the value can't be decoded instead of panicking.
+    fn try_get_from(key : &str, immutable_map : &ScImmutableMap) -> Result<Self, DecodeError> {
+        Ok(Self::get_from(key, immutable_map))
+    }
+    /// Checks if a value exists under 'key' in 'immutable_map'. Returns true if it exists.
+    fn exists_in(key : &str, immutable_map : &ScImmutableMap) -> bool;
+    /// Describes the value for humans, e.g. in state diffs. Defaults to the name of the type.
+    fn describe(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
+}
+
+macro_rules! add_impl_map_value {
+    ($value_type:ty, $get_func:ident, $value:ident => $param:expr, $described:ident => $description:expr) => {
+        /// Adds support for reading and writing the type from/to wasmlib maps.
+        impl MapValue for $value_type {
+            fn set_in(&self, key : &str, mutable_map : &ScMutableMap) {
+                let $value = self;
+                mutable_map.$get_func(key).set_value($param);
+            }
+
+            fn get_from(key : &str, immutable_map : &ScImmutableMap) -> Self {
+                immutable_map.$get_func(key).value()
+            }
+
+            fn exists_in(key : &str, immutable_map : &ScImmutableMap) -> bool {
+                immutable_map.$get_func(key).exists()
+            }
+
+            fn describe(&self) -> String {
+                let $described = self;
+                $description
+            }
+        }
+    };
+}
+
+// Primitive types
+add_impl_map_value!(String, get_string, value => value.as_str(), value => format!("{:?}", value));
+add_impl_map_value!(i64, get_int64, value => *value, value => value.to_string());
+add_impl_map_value!(Vec<u8>, get_bytes, value => value.as_slice(), value => crate::codec::to_hex(value));
+
+// ISCP types
+add_impl_map_value!(ScAgentID, get_agent_id, value => value, value => value.to_string());
+add_impl_map_value!(ScAddress, get_address, value => value, value => value.to_string());
+add_impl_map_value!(ScRequestID, get_request_id, value => value, value => value.to_string());
+add_impl_map_value!(ScHname, get_hname, value => ScHname(value.0), value => value.to_string());
+add_impl_map_value!(ScHash, get_hash, value => value, value => value.to_string());
+add_impl_map_value!(ScColor, get_color, value => value, value => value.to_string());
+add_impl_map_value!(ScChainID, get_chain_id, value => value, value => value.to_string());
+
+/// Adds support for reading and writing booleans. They are stored as a single byte, as encoded by Wasp.
+impl MapValue for bool {
+    fn set_in(&self, key : &str, mutable_map : &ScMutableMap) {
+        let bool_value = crate::wire::encode_bool(*self);
+        mutable_map.get_bytes(key).set_value(&bool_value);
+    }
+
+    fn get_from(key : &str, immutable_map : &ScImmutableMap) -> Self {
+        let param = immutable_map.get_bytes(key);
+        param.exists() && crate::results::to_bool(param.value())
+    }
+
+    fn try_get_from(key : &str, immutable_map : &ScImmutableMap) -> Result<Self, DecodeError> {
+        let param = immutable_map.get_bytes(key);
+        match param.exists() {
+            true => crate::wire::decode_bool(&param.value()),
+            false => Ok(false),
+        }
+    }
+
+    fn exists_in(key : &str, immutable_map : &ScImmutableMap) -> bool {
+        immutable_map.get_bytes(key).exists()
+    }
+
+    fn describe(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Gets the value under 'key' in 'immutable_map'. Returns default value if it can't find it.
+/// Panics with the key and reason if it can't be decoded (the contract call stops).
+pub fn must_get_from<T : MapValue, TContext : ScBaseContext>(key : &str, immutable_map : &ScImmutableMap, ctx : &TContext) -> T {
+    match T::try_get_from(key, immutable_map) {
+        Ok(value) => value,
+        Err(error) => {
+            let message = format!("{} could not be decoded. Reason: {}", key, error);
+            ctx.panic(&message);
+            panic!("{}", message)
+        },
+    }
+}
+
+/// Reason an array can't be read from a wasmlib map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArrayError {
+    /// The array has more than the maximum amount of items.
+    TooLong { length : i32 },
+    /// The array has no item at 'index'.
+    MissingItem { index : i32 },
+    /// The item at 'index' is not a valid value of the type.
+    InvalidItem { index : i32, error : DecodeError },
+}
+
+/// Defines how a type is written to and read from wasmlib arrays, such as parameter and result arrays.
+/// Items are kept in the typed array of wasmlib for the type, so callers can pass them as such.
+pub trait ArrayValue : WireValue {
+    /// Wasmlib type id of the items of the array.
+    const TYPE_ID : i32;
+    /// Gets the items of the array 'key' in 'immutable_map'. Returns an empty Vec if it can't find it.
+    /// Fails if it has more than 'max_length' items, has holes, or an item is not valid.
+    fn get_array_from(key : &str, immutable_map : &ScImmutableMap, max_length : i32) -> Result<Vec<Self>, ArrayError>;
+    /// Replaces the items of the array 'key' in 'mutable_map' with 'items'.
+    fn set_array_in(key : &str, items : &[Self], mutable_map : &ScMutableMap);
+    /// Checks if the array 'key' exists in 'immutable_map', even if it is empty. Returns true if it exists.
+    fn array_exists_in(key : &str, immutable_map : &ScImmutableMap) -> bool {
+        host::exists(immutable_map.map_id(), key.get_key_id(), host::TYPE_ARRAY | Self::TYPE_ID)
+    }
+}
+
+macro_rules! add_impl_array_value {
+    ($value_type:ty, $type_id:ident, $get_array_func:ident, $get_item_func:ident, $item:ident => $param:expr) => {
+        /// Adds support for reading and writing arrays of the type from/to wasmlib maps.
+        impl ArrayValue for $value_type {
+            const TYPE_ID : i32 = host::$type_id;
+
+            fn get_array_from(key : &str, immutable_map : &ScImmutableMap, max_length : i32) -> Result<Vec<Self>, ArrayError> {
+                let array = immutable_map.$get_array_func(key);
+                let length = array.length();
+                if length > max_length {
+                    return Err(ArrayError::TooLong { length });
+                }
+                let mut items = Vec::with_capacity(length as usize);
+                for index in 0..length {
+                    let item = array.$get_item_func(index);
+                    if !item.exists() {
+                        return Err(ArrayError::MissingItem { index });
+                    }
+                    items.push(item.value());
+                }
+                Ok(items)
+            }
+
+            fn set_array_in(key : &str, items : &[Self], mutable_map : &ScMutableMap) {
+                let array = mutable_map.$get_array_func(key);
+                array.clear();
+                for (index, item) in items.iter().enumerate() {
+                    let $item = item;
+                    array.$get_item_func(index as i32).set_value($param);
+                }
+            }
+        }
+    };
+}
+
+// Primitive types
+add_impl_array_value!(String, TYPE_STRING, get_string_array, get_string, item => item.as_str());
+add_impl_array_value!(i64, TYPE_INT64, get_int64_array, get_int64, item => *item);
+add_impl_array_value!(Vec<u8>, TYPE_BYTES, get_bytes_array, get_bytes, item => item.as_slice());
+
+// ISCP types
+add_impl_array_value!(ScAgentID, TYPE_AGENT_ID, get_agent_id_array, get_agent_id, item => item);
+add_impl_array_value!(ScAddress, TYPE_ADDRESS, get_address_array, get_address, item => item);
+add_impl_array_value!(ScRequestID, TYPE_REQUEST_ID, get_request_id_array, get_request_id, item => item);
+add_impl_array_value!(ScHname, TYPE_HNAME, get_hname_array, get_hname, item => ScHname(item.0));
+add_impl_array_value!(ScHash, TYPE_HASH, get_hash_array, get_hash, item => item);
+add_impl_array_value!(ScColor, TYPE_COLOR, get_color_array, get_color, item => item);
+add_impl_array_value!(ScChainID, TYPE_CHAIN_ID, get_chain_id_array, get_chain_id, item => item);
+
+/// Adds support for reading and writing arrays of booleans. Wasmlib has no bool arrays, so they are kept in bytes arrays, as encoded by Wasp.
+impl ArrayValue for bool {
+    const TYPE_ID : i32 = host::TYPE_BYTES;
+
+    fn get_array_from(key : &str, immutable_map : &ScImmutableMap, max_length : i32) -> Result<Vec<Self>, ArrayError> {
+        let mut items = Vec::new();
+        for (index, bytes) in Vec::<u8>::get_array_from(key, immutable_map, max_length)?.iter().enumerate() {
+            match crate::wire::decode_bool(bytes) {
+                Ok(item) => items.push(item),
+                Err(error) => return Err(ArrayError::InvalidItem { index : index as i32, error }),
+            }
+        }
+        Ok(items)
+    }
+
+    fn set_array_in(key : &str, items : &[Self], mutable_map : &ScMutableMap) {
+        let encoded_items : Vec<Vec<u8>> = items.iter().map(|item| crate::wire::encode_bool(*item)).collect();
+        Vec::<u8>::set_array_in(key, &encoded_items, mutable_map);
+    }
+}
+
+/// Value which can be set in wasmlib maps either by value or by reference, e.g. `1i64`, `"text"` or `&agent_id`.
+pub trait IntoMapValue {
+    /// Sets the value under 'key' in 'mutable_map'.
+    fn set_into(&self, key : &str, mutable_map : &ScMutableMap);
+}
+
+impl<T : MapValue> IntoMapValue for &T {
+    fn set_into(&self, key : &str, mutable_map : &ScMutableMap) {
+        self.set_in(key, mutable_map);
+    }
+}
+
+macro_rules! add_impl_into_map_value {
+    ($($value_type:ty),+) => {
+        $(
+            impl IntoMapValue for $value_type {
+                fn set_into(&self, key : &str, mutable_map : &ScMutableMap) {
+                    self.set_in(key, mutable_map);
+                }
+            }
+        )+
+    };
+}
+
+add_impl_into_map_value!(String, i64, Vec<u8>, bool);
+add_impl_into_map_value!(ScAgentID, ScAddress, ScRequestID, ScHname, ScHash, ScColor, ScChainID);
+
+impl IntoMapValue for &str {
+    fn set_into(&self, key : &str, mutable_map : &ScMutableMap) {
+        mutable_map.get_string(key).set_value(self);
+    }
+}
+
+impl IntoMapValue for &[u8] {
+    fn set_into(&self, key : &str, mutable_map : &ScMutableMap) {
+        mutable_map.get_bytes(key).set_value(self);
+    }
+}
+
+impl<T : crate::codec::Encode + crate::codec::Decode + Default> IntoMapValue for crate::codec::Encoded<T> {
+    fn set_into(&self, key : &str, mutable_map : &ScMutableMap) {
+        self.set_in(key, mutable_map);
+    }
+}
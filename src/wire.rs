@@ -0,0 +1,178 @@
+use wasmlib::*;
+use crate::codec::{self, DecodeError};
+
+// Encodings used by Wasp and the Go wasmlib for values stored in params, state and results.
+// Functions in this module are pure, so they can also be used outside of the wasm vm.
+
+/// Length in bytes of an encoded int64.
+pub const INT64_LENGTH : usize = 8;
+/// Length in bytes of an encoded bool.
+pub const BOOL_LENGTH : usize = 1;
+
+/// Fails if 'bytes' does not have the 'expected' length.
+fn require_length(bytes : &[u8], expected : usize) -> Result<(), DecodeError> {
+    if bytes.len() != expected {
+        return Err(DecodeError::InvalidLength { expected, actual : bytes.len() });
+    }
+    Ok(())
+}
+
+// ---------------------------    Primitive types    -------------------------------------
+
+/// Encodes an int64 as 8 little-endian bytes.
+pub fn encode_int64(value : i64) -> Vec<u8> {
+    value.to_le_bytes().to_vec()
+}
+
+/// Decodes an int64 from 8 little-endian bytes.
+pub fn decode_int64(bytes : &[u8]) -> Result<i64, DecodeError> {
+    require_length(bytes, INT64_LENGTH)?;
+    let mut value = [0_u8; INT64_LENGTH];
+    value.copy_from_slice(bytes);
+    Ok(i64::from_le_bytes(value))
+}
+
+/// Encodes a string as its UTF-8 bytes, without length prefix.
+pub fn encode_string(value : &str) -> Vec<u8> {
+    value.as_bytes().to_vec()
+}
+
+/// Decodes a string from its UTF-8 bytes.
+pub fn decode_string(bytes : &[u8]) -> Result<String, DecodeError> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
+}
+
+/// Encodes bytes as they are.
+pub fn encode_bytes(value : &[u8]) -> Vec<u8> {
+    value.to_vec()
+}
+
+/// Decodes bytes as they are.
+pub fn decode_bytes(bytes : &[u8]) -> Result<Vec<u8>, DecodeError> {
+    Ok(bytes.to_vec())
+}
+
+/// Encodes a bool as a single byte: 1 for true, 0 for false.
+pub fn encode_bool(value : bool) -> Vec<u8> {
+    vec![value as u8]
+}
+
+/// Decodes a bool from a single byte. Fails if it is neither 0 nor 1.
+pub fn decode_bool(bytes : &[u8]) -> Result<bool, DecodeError> {
+    require_length(bytes, BOOL_LENGTH)?;
+    match bytes[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        other => Err(DecodeError::InvalidBool(other)),
+    }
+}
+
+// ---------------------------    ISCP types    -------------------------------------
+
+/// Type byte of ED25519 addresses, the first byte of an encoded address.
+pub const ADDRESS_TYPE_ED25519 : u8 = 0;
+/// Type byte of BLS addresses.
+pub const ADDRESS_TYPE_BLS : u8 = 1;
+/// Type byte of alias addresses, such as the address of a chain.
+pub const ADDRESS_TYPE_ALIAS : u8 = 2;
+
+/// Fails if the first byte of 'bytes' is not a known address type. Agent ids start with an address too.
+fn require_address_type(bytes : &[u8]) -> Result<(), DecodeError> {
+    match bytes[0] {
+        ADDRESS_TYPE_ED25519 | ADDRESS_TYPE_BLS | ADDRESS_TYPE_ALIAS => Ok(()),
+        other => Err(DecodeError::InvalidAddressType(other)),
+    }
+}
+
+/// Fails if 'bytes' is not an alias address, as Wasp requires for chain ids.
+fn require_alias_address_type(bytes : &[u8]) -> Result<(), DecodeError> {
+    match bytes[0] {
+        ADDRESS_TYPE_ALIAS => Ok(()),
+        other => Err(DecodeError::InvalidAddressType(other)),
+    }
+}
+
+macro_rules! add_impl_fixed_length_fns {
+    ($encode_func:ident, $decode_func:ident, $value_type:ty, $length:expr) => {
+        add_impl_fixed_length_fns!($encode_func, $decode_func, $value_type, $length, |_| Ok(()));
+    };
+    ($encode_func:ident, $decode_func:ident, $value_type:ty, $length:expr, $check:expr) => {
+        /// Encodes the value as its raw bytes.
+        pub fn $encode_func(value : &$value_type) -> Vec<u8> {
+            value.to_bytes().to_vec()
+        }
+
+        /// Decodes the value from its raw bytes. Fails if they do not have the expected length or are rejected by Wasp.
+        pub fn $decode_func(bytes : &[u8]) -> Result<$value_type, DecodeError> {
+            require_length(bytes, $length)?;
+            let check : fn(&[u8]) -> Result<(), DecodeError> = $check;
+            check(bytes)?;
+            Ok(<$value_type>::from_bytes(bytes))
+        }
+    };
+}
+
+add_impl_fixed_length_fns!(encode_agent_id, decode_agent_id, ScAgentID, codec::AGENT_ID_LENGTH, require_address_type);
+add_impl_fixed_length_fns!(encode_address, decode_address, ScAddress, codec::ADDRESS_LENGTH, require_address_type);
+add_impl_fixed_length_fns!(encode_request_id, decode_request_id, ScRequestID, codec::REQUEST_ID_LENGTH);
+add_impl_fixed_length_fns!(encode_hash, decode_hash, ScHash, codec::HASH_LENGTH);
+add_impl_fixed_length_fns!(encode_color, decode_color, ScColor, codec::COLOR_LENGTH);
+add_impl_fixed_length_fns!(encode_chain_id, decode_chain_id, ScChainID, codec::CHAIN_ID_LENGTH, require_alias_address_type);
+
+/// Encodes an hname as 4 little-endian bytes.
+pub fn encode_hname(value : ScHname) -> Vec<u8> {
+    value.0.to_le_bytes().to_vec()
+}
+
+/// Decodes an hname from 4 little-endian bytes.
+pub fn decode_hname(bytes : &[u8]) -> Result<ScHname, DecodeError> {
+    require_length(bytes, codec::HNAME_LENGTH)?;
+    let mut value = [0_u8; codec::HNAME_LENGTH];
+    value.copy_from_slice(bytes);
+    Ok(ScHname(u32::from_le_bytes(value)))
+}
+
+// ---------------------------    Wire values    -------------------------------------
+
+/// Type with an encoding used by Wasp, so it can be stored as bytes, e.g. as item of a parameter array.
+pub trait WireValue : Sized {
+    /// Name of the type, used in error messages.
+    const TYPE_NAME : &'static str;
+    /// Encodes the value.
+    fn to_wire(&self) -> Vec<u8>;
+    /// Decodes the value. Fails if 'bytes' are not a valid encoding.
+    fn from_wire(bytes : &[u8]) -> Result<Self, DecodeError>;
+}
+
+macro_rules! add_impl_wire_value {
+    ($value_type:ty, $type_name:expr, $encode_func:ident, $decode_func:ident, $value:ident => $param:expr) => {
+        /// Adds support for the encoding used by Wasp.
+        impl WireValue for $value_type {
+            const TYPE_NAME : &'static str = $type_name;
+
+            fn to_wire(&self) -> Vec<u8> {
+                let $value = self;
+                $encode_func($param)
+            }
+
+            fn from_wire(bytes : &[u8]) -> Result<Self, DecodeError> {
+                $decode_func(bytes)
+            }
+        }
+    };
+}
+
+// Primitive types
+add_impl_wire_value!(String, "string", encode_string, decode_string, value => value);
+add_impl_wire_value!(i64, "int64", encode_int64, decode_int64, value => *value);
+add_impl_wire_value!(Vec<u8>, "bytes", encode_bytes, decode_bytes, value => value);
+add_impl_wire_value!(bool, "bool", encode_bool, decode_bool, value => *value);
+
+// ISCP types
+add_impl_wire_value!(ScAgentID, "agent id", encode_agent_id, decode_agent_id, value => value);
+add_impl_wire_value!(ScAddress, "address", encode_address, decode_address, value => value);
+add_impl_wire_value!(ScRequestID, "request id", encode_request_id, decode_request_id, value => value);
+add_impl_wire_value!(ScHname, "hname", encode_hname, decode_hname, value => ScHname(value.0));
+add_impl_wire_value!(ScHash, "hash", encode_hash, decode_hash, value => value);
+add_impl_wire_value!(ScColor, "color", encode_color, decode_color, value => value);
+add_impl_wire_value!(ScChainID, "chain id", encode_chain_id, decode_chain_id, value => value);
@@ -0,0 +1,59 @@
+use iota_sc_utils::codec::{Decode, DecodeError, Encode};
+use iota_sc_utils::derive::*;
+
+#[derive(Encode, Decode, MapValue, Debug, PartialEq)]
+enum Kind {
+    Empty,
+    Amount(i64),
+    Named { name : String, flag : bool },
+}
+
+/// MapValue reads missing values as the default value.
+impl Default for Kind {
+    fn default() -> Kind {
+        Kind::Empty
+    }
+}
+
+/// Types may derive Decode without Encode, and the other way around.
+#[derive(Decode, Debug, PartialEq)]
+struct DecodeOnly {
+    id : u16,
+}
+
+#[derive(Encode)]
+struct EncodeOnly {
+    id : u16,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Generic<T>(T, Option<T>);
+
+#[test]
+fn enum_variants_are_encoded_as_their_index() {
+    assert_eq!(Kind::Empty.encode(), vec![0]);
+    assert_eq!(Kind::Amount(-1).encode(), vec![1, 1]);
+    assert_eq!(Kind::Named { name : "a".to_string(), flag : true }.encode(), vec![2, 1, b'a', 1]);
+    assert_eq!(Kind::decode(&[1, 1]), Ok(Kind::Amount(-1)));
+    assert_eq!(Kind::decode(&[2, 1, b'a', 1]), Ok(Kind::Named { name : "a".to_string(), flag : true }));
+}
+
+#[test]
+fn unknown_enum_variants_are_rejected() {
+    assert_eq!(Kind::decode(&[3]), Err(DecodeError::InvalidVariant { type_name : "Kind", variant : 3 }));
+    assert_eq!(Kind::decode(&[0x80, 0x00]), Err(DecodeError::InvalidInteger));
+    assert_eq!(Kind::decode(&[0, 0]), Err(DecodeError::TrailingBytes(1)));
+}
+
+#[test]
+fn one_sided_derives_work() {
+    assert_eq!(EncodeOnly { id : 300 }.encode(), vec![0xac, 0x02]);
+    assert_eq!(DecodeOnly::decode(&[0xac, 0x02]), Ok(DecodeOnly { id : 300 }));
+}
+
+#[test]
+fn generic_types_roundtrip() {
+    let value = Generic(5_u8, Some(6_u8));
+    assert_eq!(value.encode(), vec![5, 1, 6]);
+    assert_eq!(Generic::<u8>::decode(&[5, 0]), Ok(Generic(5, None)));
+}
@@ -0,0 +1,146 @@
+// Generates wire_vectors.txt with the codec of Wasp (packages/kv/codec), so the Rust encoding is checked against the Go one.
+// Run from a checkout of github.com/iotaledger/wasp (v0.2.x):
+//     go run generate_wire_vectors.go > wire_vectors.txt
+package main
+
+import (
+	"encoding/hex"
+	"fmt"
+	"strings"
+
+	"github.com/iotaledger/wasp/packages/iscp"
+	"github.com/iotaledger/wasp/packages/kv/codec"
+)
+
+// Writes a vector, or a failing one ('!') if decoding returned an error.
+func vector(typeName string, value string, encoded []byte, err error) {
+	if err != nil {
+		value = "!"
+	}
+	fmt.Printf("%s|%s|%s\n", typeName, value, hex.EncodeToString(encoded))
+}
+
+func mustHex(s string) []byte {
+	bytes, err := hex.DecodeString(s)
+	if err != nil {
+		panic(err)
+	}
+	return bytes
+}
+
+// Returns raw bytes of 'size' bytes: all zero, counting up from 1, counting after an alias address type byte (2),
+// all 0xff, and two with an invalid length. The first byte of addresses, agent ids and chain ids is the address type.
+func rawInputs(size int) [][]byte {
+	counting := make([]byte, size)
+	for i := range counting {
+		counting[i] = byte(i + 1)
+	}
+	alias := append([]byte{2}, counting[1:]...)
+	return [][]byte{
+		make([]byte, size),
+		counting,
+		alias,
+		mustHex(strings.Repeat("ff", size)),
+		make([]byte, size-1),
+		make([]byte, size+1),
+	}
+}
+
+// Decodes every raw input and encodes it back. Values of these types are written as hex of their raw bytes.
+func raw(typeName string, size int, roundTrip func([]byte) ([]byte, error)) {
+	for _, input := range rawInputs(size) {
+		encoded, err := roundTrip(input)
+		if err != nil {
+			vector(typeName, "", input, err)
+			continue
+		}
+		vector(typeName, hex.EncodeToString(encoded), encoded, nil)
+	}
+}
+
+func main() {
+	fmt.Println("# Golden vectors of the encoding used by Wasp and the Go wasmlib (packages/kv/codec).")
+	fmt.Println("# Generated by tests/golden/generate_wire_vectors.go. Don't edit by hand, run the generator instead.")
+	fmt.Println("# Format: <type>|<value>|<hex of encoded bytes>")
+	fmt.Println("# Values: int64 and hname in decimal, string as text, bool as true/false, every other type as hex of its raw bytes.")
+	fmt.Println("# A value of '!' means decoding the bytes must fail.")
+
+	for _, value := range []int64{0, 1, -1, 255, 256, 1000000, 9223372036854775807, -9223372036854775808} {
+		vector("int64", fmt.Sprint(value), codec.EncodeInt64(value), nil)
+	}
+	for _, input := range []string{"01020304050607", "010203040506070809"} {
+		_, _, err := codec.DecodeInt64(mustHex(input))
+		vector("int64", "", mustHex(input), err)
+	}
+
+	for _, value := range []string{"", "a", "hello world", "héllo", "名前"} {
+		vector("string", value, codec.EncodeString(value), nil)
+	}
+	for _, input := range []string{"c328"} {
+		_, _, err := codec.DecodeString(mustHex(input))
+		vector("string", "", mustHex(input), err)
+	}
+
+	for _, value := range []string{"", "00", "0102fe", "deadbeef"} {
+		vector("bytes", value, mustHex(value), nil)
+	}
+
+	for _, value := range []bool{false, true} {
+		vector("bool", fmt.Sprint(value), codec.EncodeBool(value), nil)
+	}
+	for _, input := range []string{"", "02", "0100"} {
+		_, _, err := codec.DecodeBool(mustHex(input))
+		vector("bool", "", mustHex(input), err)
+	}
+
+	for _, value := range []uint32{0, 1, 1011572226, 4294967295} {
+		vector("hname", fmt.Sprint(value), codec.EncodeHname(iscp.Hname(value)), nil)
+	}
+	for _, input := range []string{"010203"} {
+		_, _, err := codec.DecodeHname(mustHex(input))
+		vector("hname", "", mustHex(input), err)
+	}
+
+	raw("agent_id", iscp.AgentIDLength, func(input []byte) ([]byte, error) {
+		value, _, err := codec.DecodeAgentID(input)
+		if err != nil {
+			return nil, err
+		}
+		return codec.EncodeAgentID(value), nil
+	})
+	raw("address", 33, func(input []byte) ([]byte, error) {
+		value, _, err := codec.DecodeAddress(input)
+		if err != nil {
+			return nil, err
+		}
+		return codec.EncodeAddress(value), nil
+	})
+	raw("request_id", 34, func(input []byte) ([]byte, error) {
+		value, _, err := codec.DecodeRequestID(input)
+		if err != nil {
+			return nil, err
+		}
+		return codec.EncodeRequestID(value), nil
+	})
+	raw("hash", 32, func(input []byte) ([]byte, error) {
+		value, _, err := codec.DecodeHashValue(input)
+		if err != nil {
+			return nil, err
+		}
+		return codec.EncodeHashValue(value), nil
+	})
+	raw("color", 32, func(input []byte) ([]byte, error) {
+		value, _, err := codec.DecodeColor(input)
+		if err != nil {
+			return nil, err
+		}
+		return codec.EncodeColor(value), nil
+	})
+	raw("chain_id", iscp.ChainIDLength, func(input []byte) ([]byte, error) {
+		value, _, err := codec.DecodeChainID(input)
+		if err != nil {
+			return nil, err
+		}
+		return codec.EncodeChainID(value), nil
+	})
+}
@@ -0,0 +1,36 @@
+use iota_sc_utils::derive::FromParams;
+use iota_sc_utils::params;
+use iota_sc_utils::validate::{self, Rule};
+use iota_sc_utils::wasmlib::*;
+
+/// The literals of `one_of` get the type of the field, so integer literals compare with i64 fields.
+#[derive(FromParams)]
+#[allow(dead_code)]
+struct Order {
+    #[params(optional, one_of(1, 2, 3))]
+    priority : i64,
+    #[params(one_of("buy", "sell"))]
+    side : String,
+    #[params(custom_ctx = "not_owner")]
+    operator : ScAgentID,
+}
+
+fn not_owner<TContext : ScBaseContext>(agent_id : &ScAgentID, ctx : &TContext) -> Result<(), String> {
+    match *agent_id == ctx.contract_creator() {
+        true => Err("must not be the owner".to_string()),
+        false => Ok(()),
+    }
+}
+
+#[allow(dead_code)]
+fn read_order(ctx : &ScFuncContext) -> Order {
+    params::must_get_struct(ctx)
+}
+
+#[test]
+fn one_of_compares_with_the_type_of_the_field() {
+    let allowed : &[i64] = &[1, 2, 3];
+    assert_eq!(validate::one_of(allowed).check(&2i64), Ok(()));
+    assert!(validate::one_of(allowed).check(&4i64).is_err());
+    assert_eq!(validate::one_of::<&str>(&["buy", "sell"]).check(&"sell".to_string()), Ok(()));
+}
@@ -0,0 +1,98 @@
+use iota_sc_utils::wire;
+use iota_sc_utils::wasmlib::ScHname;
+
+const VECTORS : &str = include_str!("golden/wire_vectors.txt");
+
+struct Vector<'a> {
+    type_name : &'a str,
+    value : &'a str,
+    encoded : Vec<u8>,
+}
+
+fn from_hex(hex : &str) -> Vec<u8> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            assert_eq!(pair.len(), 2, "odd hex length: {}", hex);
+            u8::from_str_radix(std::str::from_utf8(pair).expect("invalid hex"), 16).expect("invalid hex")
+        })
+        .collect()
+}
+
+fn vectors() -> Vec<Vector<'static>> {
+    VECTORS.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let columns : Vec<&str> = line.split('|').collect();
+            assert_eq!(columns.len(), 3, "invalid vector: {}", line);
+            Vector { type_name : columns[0], value : columns[1], encoded : from_hex(columns[2]) }
+        })
+        .collect()
+}
+
+/// Checks a vector of a type whose value is written as hex of its raw bytes.
+macro_rules! check_raw {
+    ($vector:expr, $encode_func:path, $decode_func:path) => {
+        match $vector.value {
+            "!" => assert!($decode_func(&$vector.encoded).is_err(), "{} {:?} should not decode", $vector.type_name, $vector.encoded),
+            value => {
+                let decoded = $decode_func(&$vector.encoded).expect("valid vector should decode");
+                assert_eq!($encode_func(&decoded), from_hex(value));
+                assert_eq!($encode_func(&decoded), $vector.encoded);
+            }
+        }
+    };
+}
+
+#[test]
+fn rust_encoding_matches_go_golden_vectors() {
+    let vectors = vectors();
+    assert!(!vectors.is_empty());
+
+    for vector in vectors {
+        match vector.type_name {
+            "int64" => match vector.value {
+                "!" => assert!(wire::decode_int64(&vector.encoded).is_err()),
+                value => {
+                    let value : i64 = value.parse().expect("invalid int64 vector");
+                    assert_eq!(wire::encode_int64(value), vector.encoded);
+                    assert_eq!(wire::decode_int64(&vector.encoded), Ok(value));
+                }
+            },
+            "string" => match vector.value {
+                "!" => assert!(wire::decode_string(&vector.encoded).is_err()),
+                value => {
+                    assert_eq!(wire::encode_string(value), vector.encoded);
+                    assert_eq!(wire::decode_string(&vector.encoded), Ok(value.to_string()));
+                }
+            },
+            "bytes" => {
+                assert_eq!(wire::encode_bytes(&from_hex(vector.value)), vector.encoded);
+                assert_eq!(wire::decode_bytes(&vector.encoded), Ok(from_hex(vector.value)));
+            },
+            "bool" => match vector.value {
+                "!" => assert!(wire::decode_bool(&vector.encoded).is_err()),
+                value => {
+                    let value = value == "true";
+                    assert_eq!(wire::encode_bool(value), vector.encoded);
+                    assert_eq!(wire::decode_bool(&vector.encoded), Ok(value));
+                }
+            },
+            "hname" => match vector.value {
+                "!" => assert!(wire::decode_hname(&vector.encoded).is_err()),
+                value => {
+                    let value : u32 = value.parse().expect("invalid hname vector");
+                    assert_eq!(wire::encode_hname(ScHname(value)), vector.encoded);
+                    assert_eq!(wire::decode_hname(&vector.encoded).map(|hname| hname.0), Ok(value));
+                }
+            },
+            "agent_id" => check_raw!(vector, wire::encode_agent_id, wire::decode_agent_id),
+            "address" => check_raw!(vector, wire::encode_address, wire::decode_address),
+            "request_id" => check_raw!(vector, wire::encode_request_id, wire::decode_request_id),
+            "hash" => check_raw!(vector, wire::encode_hash, wire::decode_hash),
+            "color" => check_raw!(vector, wire::encode_color, wire::decode_color),
+            "chain_id" => check_raw!(vector, wire::encode_chain_id, wire::decode_chain_id),
+            other => panic!("unknown vector type {}", other),
+        }
+    }
+}